@@ -1 +1,28 @@
-from ._windows_fonts import FontCollection, FontFamily, FontVariant, Style, Weight, get_matching_variants
+from ._windows_fonts import (
+    ExtendedMetrics,
+    FontCollection,
+    FontFace,
+    FontFamily,
+    FontMetrics,
+    FontReadError,
+    FontSetBuilder,
+    FontSnapshot,
+    FontVariant,
+    ScaledMetrics,
+    Style,
+    VerticalMetrics,
+    Weight,
+    capabilities,
+    default_fallback_font,
+    families_by_generic,
+    find_by_wss_family,
+    get_matching_variants,
+    iter_matching_variants,
+    open_font,
+    reload_locale,
+    shutdown,
+    system_ui_font,
+    theme_font,
+    variant_from_face,
+    warm_up,
+)
@@ -1,23 +1,88 @@
 from __future__ import annotations
+import datetime
 import enum
+import os
+import pathlib
+from typing import Callable, Iterable, Iterator, Literal, Mapping, NamedTuple, Sequence, overload
 
 class FontCollection:
     def __len__(self) -> int: ...
     def __getitem__(self, key: int | str) -> FontFamily: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __hash__(self) -> int: ...
+    def get_exact(self, name: str) -> FontFamily: ...
+    def family_for_file(self, path: str | os.PathLike[str], face_index: int = 0) -> FontFamily | None: ...
+    def family_at(self, index: int) -> str: ...
+    def names_set(self) -> set[str]: ...
+    def iter_with_names(self) -> Iterator[tuple[str, FontFamily]]: ...
+    def find_all(self, name: str) -> list[FontFamily]: ...
+    def snapshot(self) -> list[FontSnapshot]: ...
+    def diff(self, other: FontCollection) -> tuple[list[str], list[str], list[str]]: ...
+    def filter(self, predicate: Callable[[FontFamily], object]) -> list[FontFamily]: ...
+    @classmethod
+    def from_font_set(cls, builder: FontSetBuilder) -> FontCollection: ...
+    @classmethod
+    def from_font_set_with_filter(
+        cls,
+        *,
+        collection: FontCollection | None = None,
+        win32_family_names: str | Sequence[str] | None = None,
+        typographic_family_names: str | Sequence[str] | None = None,
+        full_name: str | Sequence[str] | None = None,
+        postscript_name: str | Sequence[str] | None = None,
+        weight_stretch_style_family_name: str | Sequence[str] | None = None,
+        design_script_language_tag: str | Sequence[str] | None = None,
+        supported_script_language_tag: str | Sequence[str] | None = None,
+        preferred_family_names: str | Sequence[str] | None = None,
+        preferred_subfamily_names: str | Sequence[str] | None = None,
+    ) -> FontCollection: ...
+    @classmethod
+    def installed_for(cls, scope: str) -> FontCollection: ...
+
+class FontSnapshot:
+    family: str
+    name: str
+    weight: int
+    style: Style
+    stretch: int
+    files: list[str]
+    face_index: int
+    information: dict[str, str]
+
+class FontSetBuilder:
+    def __init__(self) -> None: ...
+    def add_file(self, path: str | os.PathLike[str]) -> None: ...
+    def add_bytes(self, data: bytes) -> None: ...
+    def build(self) -> FontCollection: ...
 
 class FontFamily:
     name: str
+    typographic_family_name: str | None
+    preferred_family_name: str | None
+    regular: FontVariant | None
+    bold: FontVariant | None
+    italic: FontVariant | None
+    bold_italic: FontVariant | None
     def __len__(self) -> int: ...
-    def __getitem__(self, idx: int) -> FontVariant: ...
+    def __getitem__(self, key: int | str) -> FontVariant: ...
+    def __contains__(self, name: object) -> bool: ...
+    def files(self) -> list[tuple[str, list[int]]]: ...
+    def available_weights(self) -> list[int]: ...
+    def available_stretches(self) -> list[int]: ...
+    def best_variant_for_text(self, text: str) -> FontVariant | None: ...
     def get_matching_variants(
         self,
         *,
-        weight: float | Weight | None = None,
+        weight: float | Weight | tuple[float | Weight, float | Weight] | None = None,
         style: Style | None = None,
-        width: float | None = None,
-        slant: float | None = None,
-        optical_size: float | None = None,
+        width: float | tuple[float, float] | None = None,
+        slant: float | tuple[float, float] | None = None,
+        optical_size: float | tuple[float, float] | None = None,
         italic: bool | None = None,
+        at_size: float | None = None,
+        custom_axes: Mapping[str, float] | None = None,
+        exclude_simulated: bool = False,
+        limit: int | None = None,
     ) -> list[FontVariant]: ...
     def get_best_variant(
         self,
@@ -28,13 +93,213 @@ class FontFamily:
         slant: float | None = None,
         optical_size: float | None = None,
         italic: bool | None = None,
+        at_size: float | None = None,
+        custom_axes: Mapping[str, float] | None = None,
     ) -> FontVariant: ...
 
 class FontVariant:
     style: Style
     weight: Weight
+    nearest_named_weight: Weight
+    weight_name: str
+    is_standard_weight: bool
+    is_bold: bool
+    is_regular: bool
+    is_light: bool
+    stretch_value: int
+    stretch_name: str
+    family_key: tuple[int, int]
+    classification: Classification
     filename: str
-    def files(self) -> list[str]: ...
+    file_path: pathlib.Path
+    face_index: int
+    is_installed: bool
+    install_scope: str | None
+    source: str
+    metrics: FontMetrics
+    metrics_extended: ExtendedMetrics
+    vertical_metrics: VerticalMetrics
+    information: InformationDict
+    weight_stretch_style_family_name: str | None
+    style_name: str | None
+    designed_for: list[str]
+    designer: str | None
+    manufacturer: str | None
+    copyright: str | None
+    trademark: str | None
+    version_string: str | None
+    vendor_id: str | None
+    flags: frozenset[str]
+    simulations: frozenset[str]
+    is_eudc: bool
+    is_empty_font: bool
+    is_scalable: bool
+    has_vertical_metrics: bool
+    created: datetime.datetime | None
+    modified: datetime.datetime | None
+    def __format__(self, spec: str) -> str: ...
+    def files(self, verify: bool = False) -> list[str]: ...
+    def file_paths(self) -> list[pathlib.Path]: ...
+    def read_bytes(self) -> bytes: ...
+    def typographic_names(self) -> tuple[str | None, str | None]: ...
+    def hb_face(self) -> tuple[bytes, int]: ...
+    def to_woff2(self) -> bytes: ...
+    def strip_to_subset(self, codepoints: Iterable[int | str]) -> bytes: ...
+    def pillow_spec(self, size: int = 10) -> dict[str, str | int]: ...
+    def matplotlib_fontproperties(self) -> dict[str, str | int]: ...
+    def to_json(self) -> str: ...
+    def outline_svg(self, char: str, size: float = 1.0) -> str: ...
+    def cmap_subtables(self) -> list[tuple[int, int, int]]: ...
+    def supported_scripts(self) -> frozenset[str]: ...
+    def get_table(self, tag: str) -> bytes | None: ...
+    def glyph_name(self, glyph_id: int) -> str | None: ...
+    def open_face(self) -> FontFace: ...
+    def name_record(
+        self, name_id: int, platform: int = 3, encoding: int | None = None, language: int | None = None
+    ) -> str | None: ...
+    def glyph_image_formats(self) -> frozenset[str]: ...
+    def embedded_bitmap_sizes(self) -> list[int]: ...
+    def best_size(self, target_px: float) -> int | None: ...
+    def design_axis_ranges(self) -> list[tuple[str, float, float, float]]: ...
+    def design_coordinates(self) -> dict[str, float]: ...
+    def font_variation_settings(self) -> str: ...
+    def axis_value(self, tag: str) -> float | None: ...
+    def same_face(self, other: FontVariant) -> bool: ...
+    def __copy__(self) -> FontVariant: ...
+    def __deepcopy__(self, memo: dict[int, object]) -> FontVariant: ...
+    def __getstate__(self) -> tuple[str, int, list[str]]: ...
+    def __setstate__(self, state: tuple[str | os.PathLike[str], int, list[str]]) -> None: ...
+    def clone_with_simulations(self, bold: bool = False, oblique: bool = False) -> FontVariant: ...
+    def gdi_compatible_metrics(self, size_px: float, dpi: float = 96.0) -> FontMetrics: ...
+    def scaled_metrics(self, size_px: float) -> ScaledMetrics: ...
+    def metrics_for_dpi(self, size_pt: float, dpi: float = 96.0) -> ScaledMetrics: ...
+    def underline(self, size_px: float) -> tuple[float, float]: ...
+    def strikethrough(self, size_px: float) -> tuple[float, float]: ...
+    def has_character(self, char: str) -> bool: ...
+    def unicode_ranges(self) -> list[tuple[int, int]]: ...
+    def has_characters(self, start: int, end: int) -> list[int]: ...
+    def coverage_ratio(self, text: str) -> float: ...
+    def glyph_advances(self, text: str, size_px: float) -> list[float]: ...
+    def glyph_run_width(
+        self, text: str, size_px: float, shaped: bool = False, features: Mapping[str, int] | None = None
+    ) -> float: ...
+    @overload
+    def render_glyph(
+        self, char: str, size_px: float, as_buffer: Literal[False] = False
+    ) -> tuple[int, int, bytes]: ...
+    @overload
+    def render_glyph(self, char: str, size_px: float, as_buffer: Literal[True]) -> tuple[int, int, memoryview]: ...
+    @overload
+    def render_run(
+        self,
+        glyph_ids: Sequence[int],
+        advances: Sequence[float] | None,
+        offsets: Sequence[tuple[float, float]] | None,
+        size_px: float,
+        as_buffer: Literal[False] = False,
+    ) -> tuple[int, int, bytes]: ...
+    @overload
+    def render_run(
+        self,
+        glyph_ids: Sequence[int],
+        advances: Sequence[float] | None,
+        offsets: Sequence[tuple[float, float]] | None,
+        size_px: float,
+        as_buffer: Literal[True],
+    ) -> tuple[int, int, memoryview]: ...
+    def color_glyph_run(self, char: str, size_px: float) -> list[tuple[int, tuple[int, int, int, int] | None]]: ...
+    def render_to_png(
+        self,
+        text: str,
+        size_px: float,
+        foreground: tuple[int, int, int, int] = (0, 0, 0, 255),
+        background: tuple[int, int, int, int] = (255, 255, 255, 255),
+        padding: int = 4,
+        features: Mapping[str, int] | None = None,
+    ) -> bytes: ...
+    def raster_preview_ascii(self, width: int, text: str, blocks: bool = False) -> str: ...
+
+class Classification(NamedTuple):
+    weight: Weight
+    style: Style
+    stretch: int
+
+class FontFace:
+    metrics: FontMetrics
+    face_index: int
+    raw_face_ptr: int
+    def glyph_indices(self, text: str) -> list[int]: ...
+    def glyph_advances(self, text: str, size_px: float) -> list[float]: ...
+    def glyph_run_width(
+        self, text: str, size_px: float, shaped: bool = False, features: Mapping[str, int] | None = None
+    ) -> float: ...
+    def get_table(self, tag: str) -> bytes | None: ...
+    def outline_svg(self, char: str, size: float = 1.0) -> str: ...
+
+class InformationDict(Mapping[str | int, str]):
+    def __eq__(self, other: object) -> bool: ...
+    def __bool__(self) -> bool: ...
+
+class FontMetrics:
+    design_units_per_em: int
+    ascent: int
+    descent: int
+    line_gap: int
+    cap_height: int
+    x_height: int
+    underline_position: int
+    underline_thickness: int
+    strikethrough_position: int
+    strikethrough_thickness: int
+
+class ExtendedMetrics:
+    design_units_per_em: int
+    ascent: int
+    descent: int
+    line_gap: int
+    cap_height: int
+    x_height: int
+    underline_position: int
+    underline_thickness: int
+    strikethrough_position: int
+    strikethrough_thickness: int
+    glyph_box_left: int | None
+    glyph_box_top: int | None
+    glyph_box_right: int | None
+    glyph_box_bottom: int | None
+    subscript_position_x: int | None
+    subscript_position_y: int | None
+    superscript_position_x: int | None
+    superscript_position_y: int | None
+    has_typographic_metrics: bool | None
+    caret_slope_rise: int | None
+    caret_slope_run: int | None
+    caret_offset: int | None
+
+class ScaledMetrics:
+    size_px: float
+    ascent: float
+    descent: float
+    line_gap: float
+    line_height: float
+    cap_height: float
+    x_height: float
+    underline_position: float
+    underline_thickness: float
+    strikethrough_position: float
+    strikethrough_thickness: float
+
+class VerticalMetrics:
+    subscript_size_x: int
+    subscript_size_y: int
+    subscript_offset_x: int
+    subscript_offset_y: int
+    superscript_size_x: int
+    superscript_size_y: int
+    superscript_offset_x: int
+    superscript_offset_y: int
+
+class FontReadError(FileNotFoundError): ...
 
 class Style(enum.Enum):
     NORMAL = ...
@@ -54,13 +319,56 @@ class Weight(enum.Enum):
     EXTRA_BLACK = ...
 
 def get_matching_variants(
-    win32_family_names: str | None = None,
-    typographic_family_names: str | None = None,
-    full_name: str | None = None,
-    postscript_name: str | None = None,
-    weight_stretch_style_family_name: str | None = None,
-    design_script_language_tag: str | None = None,
-    supported_script_language_tag: str | None = None,
-    preferred_family_names: str | None = None,
-    preferred_subfamily_names: str | None = None,
+    *,
+    collection: FontCollection | None = None,
+    limit: int | None = None,
+    win32_family_names: str | Sequence[str] | None = None,
+    typographic_family_names: str | Sequence[str] | None = None,
+    full_name: str | Sequence[str] | None = None,
+    postscript_name: str | Sequence[str] | None = None,
+    weight_stretch_style_family_name: str | Sequence[str] | None = None,
+    design_script_language_tag: str | Sequence[str] | None = None,
+    supported_script_language_tag: str | Sequence[str] | None = None,
+    preferred_family_names: str | Sequence[str] | None = None,
+    preferred_subfamily_names: str | Sequence[str] | None = None,
 ) -> list[FontVariant]: ...
+
+def iter_matching_variants(
+    *,
+    collection: FontCollection | None = None,
+    win32_family_names: str | Sequence[str] | None = None,
+    typographic_family_names: str | Sequence[str] | None = None,
+    full_name: str | Sequence[str] | None = None,
+    postscript_name: str | Sequence[str] | None = None,
+    weight_stretch_style_family_name: str | Sequence[str] | None = None,
+    design_script_language_tag: str | Sequence[str] | None = None,
+    supported_script_language_tag: str | Sequence[str] | None = None,
+    preferred_family_names: str | Sequence[str] | None = None,
+    preferred_subfamily_names: str | Sequence[str] | None = None,
+) -> Iterator[FontVariant]: ...
+
+def find_by_wss_family(name: str) -> list[FontVariant]: ...
+
+def open_font(path: str | os.PathLike[str], face_index: int = 0) -> FontVariant: ...
+
+def variant_from_face(raw_face_ptr: int) -> FontVariant: ...
+
+def system_ui_font() -> FontVariant: ...
+
+def theme_font(role: Literal["caption", "smcaption", "menu", "message", "status"]) -> FontVariant: ...
+
+def default_fallback_font(codepoint: int | None = None) -> FontVariant: ...
+
+def families_by_generic(
+    generic: Literal["serif", "sans-serif", "monospace", "cursive", "fantasy"],
+    *,
+    collection: FontCollection | None = None,
+) -> list[FontFamily]: ...
+
+def shutdown() -> None: ...
+
+def warm_up() -> None: ...
+
+def reload_locale() -> str: ...
+
+def capabilities() -> dict[str, bool]: ...
@@ -0,0 +1,162 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use windows::core::implement;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::USER_LOCALE;
+
+/// A text analysis source for a single run of text in the user's locale, which is all font fallback and shaping need
+#[implement(IDWriteTextAnalysisSource)]
+pub struct TextSource {
+    text: Vec<u16>,
+    // Null terminated
+    locale: Vec<u16>,
+}
+
+#[allow(non_snake_case)]
+impl IDWriteTextAnalysisSource_Impl for TextSource {
+    fn GetTextAtPosition(
+        &self,
+        textposition: u32,
+        textstring: *mut *mut u16,
+        textlength: *mut u32,
+    ) -> windows::core::Result<()> {
+        let pos = textposition as usize;
+        unsafe {
+            if pos < self.text.len() {
+                *textstring = self.text.as_ptr().add(pos) as *mut u16;
+                *textlength = (self.text.len() - pos) as u32;
+            } else {
+                *textstring = std::ptr::null_mut();
+                *textlength = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn GetTextBeforePosition(
+        &self,
+        textposition: u32,
+        textstring: *mut *mut u16,
+        textlength: *mut u32,
+    ) -> windows::core::Result<()> {
+        let pos = textposition as usize;
+        unsafe {
+            if pos > 0 && pos <= self.text.len() {
+                *textstring = self.text.as_ptr() as *mut u16;
+                *textlength = pos as u32;
+            } else {
+                *textstring = std::ptr::null_mut();
+                *textlength = 0;
+            }
+        }
+        Ok(())
+    }
+
+    fn GetParagraphReadingDirection(&self) -> DWRITE_READING_DIRECTION {
+        DWRITE_READING_DIRECTION_LEFT_TO_RIGHT
+    }
+
+    fn GetLocaleName(
+        &self,
+        textposition: u32,
+        textlength: *mut u32,
+        localename: *mut *mut u16,
+    ) -> windows::core::Result<()> {
+        unsafe {
+            *textlength = (self.text.len() as u32).saturating_sub(textposition);
+            *localename = self.locale.as_ptr() as *mut u16;
+        }
+        Ok(())
+    }
+
+    fn GetNumberSubstitution(
+        &self,
+        textposition: u32,
+        textlength: *mut u32,
+        numbersubstitution: *mut Option<IDWriteNumberSubstitution>,
+    ) -> windows::core::Result<()> {
+        unsafe {
+            *textlength = (self.text.len() as u32).saturating_sub(textposition);
+            *numbersubstitution = None;
+        }
+        Ok(())
+    }
+}
+
+impl TextSource {
+    /// A source for `text`, in UTF-16
+    pub fn create(text: Vec<u16>) -> IDWriteTextAnalysisSource {
+        let mut locale = USER_LOCALE.with(|locale| locale.borrow().as_wide().to_vec());
+        locale.push(0);
+        TextSource { text, locale }.into()
+    }
+}
+
+/// The script of each run of text found by ``IDWriteTextAnalyzer::AnalyzeScript``, as `(position, length, analysis)`
+pub type ScriptRuns = Rc<RefCell<Vec<(u32, u32, DWRITE_SCRIPT_ANALYSIS)>>>;
+
+/// The resolved bidi level of each run of text found by ``IDWriteTextAnalyzer::AnalyzeBidi``, as
+/// `(position, length, level)`. Odd levels are right-to-left
+pub type BidiRuns = Rc<RefCell<Vec<(u32, u32, u8)>>>;
+
+/// A text analysis sink that only records the script analysis and bidi levels, into lists shared with the caller
+#[implement(IDWriteTextAnalysisSink)]
+pub struct ScriptSink {
+    runs: ScriptRuns,
+    levels: BidiRuns,
+}
+
+impl ScriptSink {
+    pub fn create(runs: ScriptRuns, levels: BidiRuns) -> IDWriteTextAnalysisSink {
+        ScriptSink { runs, levels }.into()
+    }
+}
+
+#[allow(non_snake_case)]
+impl IDWriteTextAnalysisSink_Impl for ScriptSink {
+    fn SetScriptAnalysis(
+        &self,
+        textposition: u32,
+        textlength: u32,
+        scriptanalysis: *const DWRITE_SCRIPT_ANALYSIS,
+    ) -> windows::core::Result<()> {
+        let analysis = unsafe { *scriptanalysis };
+        self.runs
+            .borrow_mut()
+            .push((textposition, textlength, analysis));
+        Ok(())
+    }
+
+    fn SetLineBreakpoints(
+        &self,
+        _textposition: u32,
+        _textlength: u32,
+        _linebreakpoints: *const DWRITE_LINE_BREAKPOINT,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+
+    fn SetBidiLevel(
+        &self,
+        textposition: u32,
+        textlength: u32,
+        _explicitlevel: u8,
+        resolvedlevel: u8,
+    ) -> windows::core::Result<()> {
+        self.levels
+            .borrow_mut()
+            .push((textposition, textlength, resolvedlevel));
+        Ok(())
+    }
+
+    fn SetNumberSubstitution(
+        &self,
+        _textposition: u32,
+        _textlength: u32,
+        _numbersubstitution: &Option<IDWriteNumberSubstitution>,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,608 @@
+//! A minimal Brotli (RFC 7932) compressor, for writing WOFF2 files without needing a compression library.
+//!
+//! Matches are found greedily with a hash chain, and each meta-block is coded with a single set of prefix codes and no
+//! context modelling. That compresses a good deal less than the reference encoder, but fonts still shrink to around
+//! half their size.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Log2 of the sliding window size declared in the stream header. Must allow every distance below `MAX_DISTANCE`
+const WINDOW_BITS: u32 = 22;
+/// Matches are only searched for this far back. A power of two, as it is also the size of the hash chain ring
+const MAX_DISTANCE: usize = 1 << 20;
+const MAX_METABLOCK: usize = 1 << 20;
+const MIN_MATCH: usize = 4;
+/// How many earlier positions with the same hash are checked for a match
+const MAX_CHAIN: usize = 32;
+const HASH_BITS: u32 = 16;
+const NO_POSITION: u32 = u32::MAX;
+
+/// Alphabet sizes, and the number of bits needed for a symbol of each in a simple prefix code
+const LITERAL_ALPHABET_BITS: u32 = 8;
+const COMMAND_ALPHABET_BITS: u32 = 10;
+/// 16 special distance codes, then 48 codes for explicit distances (NPOSTFIX and NDIRECT are both 0)
+const DISTANCE_ALPHABET_BITS: u32 = 6;
+
+/// `(base, extra bits)` of each insert length code
+const INSERT_LENGTHS: [(usize, u32); 24] = [
+    (0, 0),
+    (1, 0),
+    (2, 0),
+    (3, 0),
+    (4, 0),
+    (5, 0),
+    (6, 1),
+    (8, 1),
+    (10, 2),
+    (14, 2),
+    (18, 3),
+    (26, 3),
+    (34, 4),
+    (50, 4),
+    (66, 5),
+    (98, 5),
+    (130, 6),
+    (194, 7),
+    (322, 8),
+    (578, 9),
+    (1090, 10),
+    (2114, 12),
+    (6210, 14),
+    (22594, 24),
+];
+
+/// `(base, extra bits)` of each copy length code
+const COPY_LENGTHS: [(usize, u32); 24] = [
+    (2, 0),
+    (3, 0),
+    (4, 0),
+    (5, 0),
+    (6, 0),
+    (7, 0),
+    (8, 0),
+    (9, 0),
+    (10, 1),
+    (12, 1),
+    (14, 2),
+    (18, 2),
+    (22, 3),
+    (30, 3),
+    (38, 4),
+    (54, 4),
+    (70, 5),
+    (102, 5),
+    (134, 6),
+    (198, 7),
+    (326, 8),
+    (582, 9),
+    (1094, 10),
+    (2118, 24),
+];
+
+/// The order code length code lengths are stored in
+const CODE_LENGTH_ORDER: [usize; 18] =
+    [1, 2, 3, 4, 0, 5, 17, 6, 16, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+
+/// The fixed `(code, bits)` used to store each code length code length, 0 to 5
+const CODE_LENGTH_LENGTH_CODES: [(u64, u32); 6] = [(0, 2), (7, 4), (3, 3), (2, 2), (1, 2), (15, 4)];
+
+/// Writes values least significant bit first, as Brotli streams are laid out
+#[derive(Default)]
+struct BitWriter {
+    out: Vec<u8>,
+    pending: u64,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, bits: u32, value: u64) {
+        debug_assert!(bits <= 32 && value >> bits == 0);
+        self.pending |= value << self.pending_bits;
+        self.pending_bits += bits;
+        while self.pending_bits >= 8 {
+            self.out.push(self.pending as u8);
+            self.pending >>= 8;
+            self.pending_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.out.push(self.pending as u8);
+        }
+        self.out
+    }
+}
+
+/// A prefix (Huffman) code, as the length and bit-reversed code of each symbol, ready for writing
+struct PrefixCode {
+    lengths: Vec<u8>,
+    codes: Vec<u16>,
+    /// Whether only one symbol is used, in which case decoders read no bits at all for it
+    lone: bool,
+}
+
+impl PrefixCode {
+    fn from_frequencies(frequencies: &[u32], max_length: u8) -> Self {
+        let lengths = code_lengths(frequencies, max_length);
+        let codes = canonical_codes(&lengths);
+        let lone = lengths.iter().filter(|&&l| l > 0).count() <= 1;
+        PrefixCode {
+            lengths,
+            codes,
+            lone,
+        }
+    }
+
+    fn write_symbol(&self, writer: &mut BitWriter, symbol: usize) {
+        if !self.lone {
+            writer.write(self.lengths[symbol] as u32, self.codes[symbol] as u64);
+        }
+    }
+
+    /// Write the description of this code, for an alphabet with symbols of `alphabet_bits` bits
+    fn write_code(&self, writer: &mut BitWriter, alphabet_bits: u32) {
+        let used: Vec<usize> = (0..self.lengths.len())
+            .filter(|&s| self.lengths[s] > 0)
+            .collect();
+        if self.lone {
+            // A simple code with one symbol
+            writer.write(2, 1);
+            writer.write(2, 0);
+            writer.write(alphabet_bits, used.first().copied().unwrap_or(0) as u64);
+            return;
+        }
+
+        let lengths = &self.lengths[..=*used.last().unwrap()];
+        let mut frequencies = [0u32; 18];
+        for &length in lengths {
+            frequencies[length as usize] += 1;
+        }
+        let length_code = PrefixCode::from_frequencies(&frequencies, 5);
+
+        // HSKIP of 0: no code length code lengths are skipped
+        writer.write(2, 0);
+        let mut space = 32;
+        for &symbol in CODE_LENGTH_ORDER.iter() {
+            let length = length_code.lengths[symbol];
+            let (code, bits) = CODE_LENGTH_LENGTH_CODES[length as usize];
+            writer.write(bits, code);
+            if length > 0 {
+                space -= 32 >> length;
+                // The decoder stops reading once the code is complete. A code with only one symbol never is, so then
+                // all 18 lengths are written
+                if space == 0 && !length_code.lone {
+                    break;
+                }
+            }
+        }
+        // Symbols after the last used one are implied to be unused, as the code is complete by then
+        for &length in lengths {
+            length_code.write_symbol(writer, length as usize);
+        }
+    }
+}
+
+/// Huffman code lengths for `frequencies`, none longer than `max_length`. Unused symbols get a length of 0, and a
+/// lone used symbol a length of 1
+fn code_lengths(frequencies: &[u32], max_length: u8) -> Vec<u8> {
+    let mut weights: Vec<u64> = frequencies.iter().map(|&f| f as u64).collect();
+    loop {
+        let lengths = huffman_lengths(&weights);
+        if lengths.iter().all(|&l| l <= max_length) {
+            return lengths;
+        }
+        // Flatten the distribution until the tree is shallow enough. Used symbols stay used
+        for weight in weights.iter_mut().filter(|w| **w > 0) {
+            // Halve, rounding up
+            *weight -= *weight / 2;
+        }
+    }
+}
+
+fn huffman_lengths(weights: &[u64]) -> Vec<u8> {
+    let mut lengths = vec![0u8; weights.len()];
+    let mut heap: BinaryHeap<Reverse<(u64, usize)>> = BinaryHeap::new();
+    // Nodes below `weights.len()` are leaves, the rest are internal nodes with their children
+    let mut children: Vec<(usize, usize)> = Vec::new();
+    for (symbol, &weight) in weights.iter().enumerate() {
+        if weight > 0 {
+            heap.push(Reverse((weight, symbol)));
+        }
+    }
+    if heap.len() == 1 {
+        let Reverse((_, symbol)) = heap.pop().unwrap();
+        lengths[symbol] = 1;
+        return lengths;
+    }
+    while heap.len() > 1 {
+        let Reverse((a_weight, a)) = heap.pop().unwrap();
+        let Reverse((b_weight, b)) = heap.pop().unwrap();
+        children.push((a, b));
+        heap.push(Reverse((
+            a_weight + b_weight,
+            weights.len() + children.len() - 1,
+        )));
+    }
+
+    if let Some(Reverse((_, root))) = heap.pop() {
+        let mut stack = vec![(root, 0u8)];
+        while let Some((node, depth)) = stack.pop() {
+            if node < weights.len() {
+                lengths[node] = depth;
+            } else {
+                let (a, b) = children[node - weights.len()];
+                stack.push((a, depth + 1));
+                stack.push((b, depth + 1));
+            }
+        }
+    }
+    lengths
+}
+
+/// The canonical code for each symbol given the code lengths, with its bits reversed as codes are written most
+/// significant bit first
+fn canonical_codes(lengths: &[u8]) -> Vec<u16> {
+    let max_length = lengths.iter().copied().max().unwrap_or(0) as usize;
+    let mut count = vec![0u16; max_length + 1];
+    for &length in lengths.iter().filter(|&&l| l > 0) {
+        count[length as usize] += 1;
+    }
+    let mut next = vec![0u32; max_length + 1];
+    let mut code = 0u32;
+    for bits in 1..=max_length {
+        next[bits] = code;
+        code = (code + count[bits] as u32) << 1;
+    }
+
+    lengths
+        .iter()
+        .map(|&length| {
+            if length == 0 {
+                return 0;
+            }
+            let code = next[length as usize];
+            next[length as usize] += 1;
+            (code as u16).reverse_bits() >> (16 - length)
+        })
+        .collect()
+}
+
+/// The length code, number of extra bits and extra bits value for `length` in a table of `(base, extra bits)`
+fn length_code(table: &[(usize, u32); 24], length: usize) -> (usize, u32, u64) {
+    let code = table.iter().rposition(|&(base, _)| base <= length).unwrap();
+    let (base, bits) = table[code];
+    (code, bits, (length - base) as u64)
+}
+
+/// The insert-and-copy length symbol for the given insert and copy length codes, from the cells that are followed by
+/// an explicit distance
+fn command_symbol(insert_code: usize, copy_code: usize) -> usize {
+    let cell = match (insert_code >> 3, copy_code >> 3) {
+        (0, 0) => 128,
+        (0, 1) => 192,
+        (0, _) => 384,
+        (1, 0) => 256,
+        (1, 1) => 320,
+        (1, _) => 512,
+        (_, 0) => 448,
+        (_, 1) => 576,
+        (_, _) => 640,
+    };
+    cell + ((insert_code & 7) << 3) + (copy_code & 7)
+}
+
+/// The distance code, number of extra bits and extra bits value for a backward `distance` of at least 1
+fn distance_code(distance: usize) -> (usize, u32, u64) {
+    // With NPOSTFIX and NDIRECT of 0, code 16 + 2 * (bits - 1) + high covers distances where
+    // distance + 3 = ((2 + high) << bits) + extra
+    let value = distance + 3;
+    let bits = (usize::BITS - 1 - value.leading_zeros()) - 1;
+    let high = (value >> bits) & 1;
+    let extra = value & ((1 << bits) - 1);
+    (16 + 2 * (bits as usize - 1) + high, bits, extra as u64)
+}
+
+/// Some literals, then a copy of earlier output (unless `copy_length` is 0, which only the last command of a
+/// meta-block may have)
+struct Command {
+    literals: std::ops::Range<usize>,
+    copy_length: usize,
+    distance: usize,
+}
+
+/// Finds matches over the whole input, so meta-blocks can refer back into earlier ones
+struct MatchFinder {
+    head: Vec<u32>,
+    previous: Vec<u32>,
+}
+
+impl MatchFinder {
+    fn new() -> Self {
+        MatchFinder {
+            head: vec![NO_POSITION; 1 << HASH_BITS],
+            previous: vec![NO_POSITION; MAX_DISTANCE],
+        }
+    }
+
+    fn hash(data: &[u8], pos: usize) -> usize {
+        let bytes = u32::from_le_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]);
+        (bytes.wrapping_mul(0x1e35_a7bd) >> (32 - HASH_BITS)) as usize
+    }
+
+    fn insert(&mut self, data: &[u8], pos: usize) {
+        if pos + 4 <= data.len() {
+            let hash = Self::hash(data, pos);
+            self.previous[pos % MAX_DISTANCE] = self.head[hash];
+            self.head[hash] = pos as u32;
+        }
+    }
+
+    /// The longest earlier match for the data at `pos`, not going past `end`, as `(length, distance)`
+    fn longest_match(&self, data: &[u8], pos: usize, end: usize) -> (usize, usize) {
+        let mut best = (0, 0);
+        if pos + MIN_MATCH > end {
+            return best;
+        }
+        let mut candidate = self.head[Self::hash(data, pos)];
+        for _ in 0..MAX_CHAIN {
+            if candidate == NO_POSITION {
+                break;
+            }
+            let start = candidate as usize;
+            // Chain entries this far back may have been overwritten by later positions
+            if pos - start >= MAX_DISTANCE {
+                break;
+            }
+            let length = data[start..]
+                .iter()
+                .zip(&data[pos..end])
+                .take_while(|(a, b)| a == b)
+                .count();
+            if length > best.0 {
+                best = (length, pos - start);
+                if pos + length == end {
+                    break;
+                }
+            }
+            candidate = self.previous[start % MAX_DISTANCE];
+        }
+        best
+    }
+
+    fn commands(&mut self, data: &[u8], start: usize, end: usize) -> Vec<Command> {
+        let mut commands = Vec::new();
+        let mut literals_start = start;
+        let mut pos = start;
+        while pos < end {
+            let (length, distance) = self.longest_match(data, pos, end);
+            self.insert(data, pos);
+            if length >= MIN_MATCH {
+                commands.push(Command {
+                    literals: literals_start..pos,
+                    copy_length: length,
+                    distance,
+                });
+                for p in pos + 1..pos + length {
+                    self.insert(data, p);
+                }
+                pos += length;
+                literals_start = pos;
+            } else {
+                pos += 1;
+            }
+        }
+        if literals_start < end {
+            commands.push(Command {
+                literals: literals_start..end,
+                copy_length: 0,
+                distance: 0,
+            });
+        }
+        commands
+    }
+}
+
+fn write_meta_block(writer: &mut BitWriter, data: &[u8], commands: &[Command], length: usize) {
+    let mut literal_frequencies = [0u32; 256];
+    let mut command_frequencies = [0u32; 704];
+    let mut distance_frequencies = [0u32; 64];
+    for command in commands {
+        for &byte in &data[command.literals.clone()] {
+            literal_frequencies[byte as usize] += 1;
+        }
+        let (insert_code, _, _) = length_code(&INSERT_LENGTHS, command.literals.len());
+        let (copy_code, _, _) = length_code(&COPY_LENGTHS, command.copy_length.max(2));
+        command_frequencies[command_symbol(insert_code, copy_code)] += 1;
+        if command.copy_length > 0 {
+            distance_frequencies[distance_code(command.distance).0] += 1;
+        }
+    }
+    let literal_code = PrefixCode::from_frequencies(&literal_frequencies, 15);
+    let command_code = PrefixCode::from_frequencies(&command_frequencies, 15);
+    let distance_code_ = PrefixCode::from_frequencies(&distance_frequencies, 15);
+
+    // ISLAST, then MLEN - 1 in as few nibbles as possible (at least 4), then ISUNCOMPRESSED
+    writer.write(1, 0);
+    let nibbles = match length - 1 {
+        n if n < 1 << 16 => 4,
+        n if n < 1 << 20 => 5,
+        _ => 6,
+    };
+    writer.write(2, nibbles - 4);
+    writer.write(nibbles as u32 * 4, (length - 1) as u64);
+    writer.write(1, 0);
+
+    // One block type each for literals, commands and distances
+    writer.write(3, 0);
+    // NPOSTFIX and NDIRECT
+    writer.write(6, 0);
+    // The literal context mode, then one prefix code each for literals and distances, so no context maps
+    writer.write(2, 0);
+    writer.write(2, 0);
+
+    literal_code.write_code(writer, LITERAL_ALPHABET_BITS);
+    command_code.write_code(writer, COMMAND_ALPHABET_BITS);
+    distance_code_.write_code(writer, DISTANCE_ALPHABET_BITS);
+
+    for command in commands {
+        let (insert_code, insert_bits, insert_extra) =
+            length_code(&INSERT_LENGTHS, command.literals.len());
+        let (copy_code, copy_bits, copy_extra) =
+            length_code(&COPY_LENGTHS, command.copy_length.max(2));
+        command_code.write_symbol(writer, command_symbol(insert_code, copy_code));
+        writer.write(insert_bits, insert_extra);
+        writer.write(copy_bits, copy_extra);
+        for &byte in &data[command.literals.clone()] {
+            literal_code.write_symbol(writer, byte as usize);
+        }
+        // The meta-block ends as soon as it has produced all its bytes, so a final literal-only command has no distance
+        if command.copy_length > 0 {
+            let (code, bits, extra) = distance_code(command.distance);
+            distance_code_.write_symbol(writer, code);
+            writer.write(bits, extra);
+        }
+    }
+}
+
+/// Compress `data` into a Brotli stream
+pub fn compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    // WBITS: a 1 bit, then WINDOW_BITS - 17 in 3 bits
+    writer.write(1, 1);
+    writer.write(3, (WINDOW_BITS - 17) as u64);
+
+    let mut finder = MatchFinder::new();
+    let mut start = 0;
+    while start < data.len() {
+        let end = (start + MAX_METABLOCK).min(data.len());
+        let commands = finder.commands(data, start, end);
+        write_meta_block(&mut writer, data, &commands, end - start);
+        start = end;
+    }
+
+    // An empty last meta-block: ISLAST and ISLASTEMPTY
+    writer.write(1, 1);
+    writer.write(1, 1);
+    writer.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    fn decompress(stream: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        brotli_decompressor::Decompressor::new(stream, 4096)
+            .read_to_end(&mut out)
+            .unwrap();
+        out
+    }
+
+    /// Bytes from a xorshift generator, which don't compress
+    fn noise(length: usize) -> Vec<u8> {
+        let mut state = 0x1234_5678u32;
+        (0..length)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state >> 7) as u8
+            })
+            .collect()
+    }
+
+    fn assert_round_trip(data: &[u8]) -> Vec<u8> {
+        let compressed = compress(data);
+        assert!(decompress(&compressed) == data);
+        compressed
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(compress(&[]), vec![0x3b]);
+        assert_round_trip(&[]);
+    }
+
+    #[test]
+    fn test_round_trip_short() {
+        assert_round_trip(b"a");
+        assert_round_trip(b"hello");
+        assert_round_trip(&(0..=255).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn test_round_trip_repetitive() {
+        let compressed = assert_round_trip(&[0; 100_000]);
+        assert!(compressed.len() < 100);
+        assert_round_trip(&b"abcabcabd".repeat(10_000));
+    }
+
+    #[test]
+    fn test_round_trip_incompressible() {
+        assert_round_trip(&noise(100_000));
+    }
+
+    #[test]
+    fn test_round_trip_multiple_meta_blocks() {
+        // Text with matches reaching back into earlier meta-blocks, and some noise so it isn't all one long match
+        let data: Vec<u8> = b"The quick brown fox jumps over the lazy dog. "
+            .iter()
+            .cycle()
+            .zip(noise(3 * MAX_METABLOCK))
+            .enumerate()
+            .map(|(i, (&text, noise))| if i % 997 == 0 { noise } else { text })
+            .collect();
+        assert_round_trip(&data);
+
+        // Matches as far back as MAX_DISTANCE
+        let mut data = noise(MAX_DISTANCE + 10);
+        data.extend_from_within(..5000);
+        data.extend_from_within(9..7000);
+        assert_round_trip(&data);
+    }
+
+    #[test]
+    fn test_canonical_codes() {
+        // The example from RFC 1951 section 3.2.2, whose codes are then bit reversed
+        let lengths = [3, 3, 3, 3, 3, 2, 4, 4];
+        let expected: [u16; 8] = [0b010, 0b011, 0b100, 0b101, 0b110, 0b00, 0b1110, 0b1111];
+        let codes = canonical_codes(&lengths);
+        for ((&code, &want), &length) in codes.iter().zip(&expected).zip(&lengths) {
+            assert_eq!(code, want.reverse_bits() >> (16 - length));
+        }
+    }
+
+    #[test]
+    fn test_code_lengths_limited() {
+        // Fibonacci frequencies make the deepest possible tree
+        let mut frequencies = vec![1u32, 1];
+        while frequencies.len() < 20 {
+            let n = frequencies.len();
+            frequencies.push(frequencies[n - 1] + frequencies[n - 2]);
+        }
+        let lengths = code_lengths(&frequencies, 15);
+        assert!(lengths.iter().all(|&l| (1..=15).contains(&l)));
+        let kraft: f64 = lengths.iter().map(|&l| 0.5f64.powi(l as i32)).sum();
+        assert_eq!(kraft, 1.0);
+    }
+
+    #[test]
+    fn test_distance_code() {
+        assert_eq!(distance_code(1), (16, 1, 0));
+        assert_eq!(distance_code(2), (16, 1, 1));
+        assert_eq!(distance_code(3), (17, 1, 0));
+        assert_eq!(distance_code(5), (18, 2, 0));
+        assert_eq!(distance_code(MAX_DISTANCE).0, 16 + 2 * 18);
+    }
+
+    #[test]
+    fn test_command_symbol() {
+        assert_eq!(command_symbol(0, 0), 128);
+        assert_eq!(command_symbol(7, 15), 192 + 63);
+        assert_eq!(command_symbol(23, 23), 703);
+    }
+}
@@ -0,0 +1,78 @@
+use std::ffi::{c_int, c_void, CStr};
+
+use pyo3::exceptions::PyBufferError;
+use pyo3::prelude::*;
+use pyo3::{ffi, AsPyPointer};
+
+use crate::render::AlphaBitmap;
+
+/// A read-only 2D buffer of bytes, for exposing bitmaps through the buffer protocol without copying them
+///
+/// Only ever used wrapped in a ``memoryview``, which is what Python code sees.
+#[pyclass(module = "windows_fonts")]
+pub struct Bitmap {
+    data: Vec<u8>,
+    // Pointed to by the `Py_buffer`s handed out, so they must live as long as this does
+    shape: [ffi::Py_ssize_t; 2],
+    strides: [ffi::Py_ssize_t; 2],
+}
+
+impl Bitmap {
+    /// A ``memoryview`` of `bitmap`, with a shape of ``(height, width)``
+    pub fn memoryview(bitmap: AlphaBitmap, py: Python<'_>) -> PyResult<PyObject> {
+        let buffer = Py::new(
+            py,
+            Bitmap {
+                data: bitmap.data,
+                shape: [bitmap.height as _, bitmap.width as _],
+                strides: [bitmap.width as _, 1],
+            },
+        )?;
+        let view = unsafe { ffi::PyMemoryView_FromObject(buffer.as_ptr()) };
+        unsafe { PyObject::from_owned_ptr_or_err(py, view) }
+    }
+}
+
+#[pymethods]
+impl Bitmap {
+    unsafe fn __getbuffer__(
+        slf: PyRef<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if flags & ffi::PyBUF_WRITABLE == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("bitmap is read-only"));
+        }
+
+        (*view).obj = slf.as_ptr();
+        ffi::Py_INCREF((*view).obj);
+        (*view).buf = slf.data.as_ptr() as *mut c_void;
+        (*view).len = slf.data.len() as _;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if flags & ffi::PyBUF_FORMAT == ffi::PyBUF_FORMAT {
+            CStr::from_bytes_with_nul(b"B\0").unwrap().as_ptr() as *mut _
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).ndim = 2;
+        (*view).shape = if flags & ffi::PyBUF_ND == ffi::PyBUF_ND {
+            slf.shape.as_ptr() as *mut _
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).strides = if flags & ffi::PyBUF_STRIDES == ffi::PyBUF_STRIDES {
+            slf.strides.as_ptr() as *mut _
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).suboffsets = std::ptr::null_mut();
+        (*view).internal = std::ptr::null_mut();
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+}
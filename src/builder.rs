@@ -0,0 +1,226 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use pyo3::exceptions::{PyIndexError, PyValueError};
+use pyo3::prelude::*;
+use windows::core::{Interface, HSTRING};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::errors::WindowsFontError;
+use crate::{FontCollection, FontFamily, FontVariant};
+
+/// Build a custom :class:`FontCollection` from font files on disk or font data in memory
+///
+/// Usage::
+///
+///     builder = FontSetBuilder()
+///     builder.add_file("C:/path/to/MyFont.ttf")
+///     builder.add_bytes(downloaded_font)
+///     collection = builder.build()
+///
+/// Each call to ``add_file`` or ``add_bytes`` adds every face in that file (so all the faces in a ``.ttc`` file are
+/// added.)
+///
+/// Needs Windows 10 Creators Update (1703) or above.
+#[pyclass(module = "windows_fonts", unsendable)]
+pub struct FontSetBuilder {
+    factory: IDWriteFactory5,
+    builder: IDWriteFontSetBuilder1,
+    // Created (and registered with the factory) the first time in-memory data is added
+    memory_loader: Option<IDWriteInMemoryFontFileLoader>,
+}
+
+impl FontSetBuilder {
+    fn _memory_loader(&mut self) -> windows::core::Result<&IDWriteInMemoryFontFileLoader> {
+        if self.memory_loader.is_none() {
+            let loader = unsafe { self.factory.CreateInMemoryFontFileLoader() }?;
+            unsafe { self.factory.RegisterFontFileLoader(&loader) }?;
+            self.memory_loader = Some(loader);
+        }
+        Ok(self.memory_loader.as_ref().unwrap())
+    }
+}
+
+impl Drop for FontSetBuilder {
+    fn drop(&mut self) {
+        // The factory is shared by the whole process, so a loader left registered would be kept (along with all the
+        // data added to it) for good. Font files already created from it hold their own reference to it, so fonts in
+        // collections built from this builder can still be read afterwards
+        if let Some(loader) = &self.memory_loader {
+            let _ = unsafe { self.factory.UnregisterFontFileLoader(loader) };
+        }
+    }
+}
+
+#[pymethods]
+impl FontSetBuilder {
+    #[new]
+    fn __new__() -> PyResult<Self> {
+        let factory: IDWriteFactory3 = unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED) }
+            .map_err(WindowsFontError::from)?;
+        let factory = factory.cast::<IDWriteFactory5>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 15063 or above".to_owned(),
+            )
+        })?;
+        let builder = unsafe { factory.CreateFontSetBuilder2() }.map_err(WindowsFontError::from)?;
+        Ok(FontSetBuilder {
+            factory,
+            builder,
+            memory_loader: None,
+        })
+    }
+
+    /// Add all the faces in the font file at `path`
+    #[pyo3(text_signature = "($self, path)")]
+    pub fn add_file(&mut self, path: PathBuf) -> PyResult<()> {
+        let path = HSTRING::from(path.to_string_lossy().as_ref());
+        unsafe {
+            let file = self
+                .factory
+                .CreateFontFileReference(&path, None)
+                .map_err(WindowsFontError::from)?;
+            self.builder
+                .AddFontFile(&file)
+                .map_err(WindowsFontError::from)?;
+        }
+        Ok(())
+    }
+
+    /// Add all the faces in the font file contained in `data`. The data is copied, so the object passed in can be
+    /// discarded afterwards
+    #[pyo3(text_signature = "($self, data)")]
+    pub fn add_bytes(&mut self, data: &[u8]) -> PyResult<()> {
+        let factory = self.factory.clone();
+        let loader = self._memory_loader().map_err(WindowsFontError::from)?;
+        unsafe {
+            // With no owner object given, DirectWrite takes its own copy of the data
+            let file = loader
+                .CreateInMemoryFontFileReference(
+                    &factory,
+                    data.as_ptr() as _,
+                    data.len() as u32,
+                    None,
+                )
+                .map_err(WindowsFontError::from)?;
+            self.builder
+                .AddFontFile(&file)
+                .map_err(WindowsFontError::from)?;
+        }
+        Ok(())
+    }
+
+    /// Create a :class:`FontCollection` containing all the fonts added so far
+    #[pyo3(text_signature = "($self)")]
+    pub(crate) fn build(&self) -> PyResult<FontCollection> {
+        let collection = unsafe {
+            self.builder
+                .CreateFontSet()
+                .and_then(|set| self.factory.CreateFontCollectionFromFontSet(&set))
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(FontCollection {
+            collection,
+            is_system: false,
+        })
+    }
+}
+
+/// Open the face at `face_index` of the font file at `path`
+///
+/// This is for picking one face out of a collection file (``.ttc``), which :class:`FontSetBuilder` would add all the
+/// faces of. Raises ``IndexError`` if the file doesn't have that many faces, and ``ValueError`` if it isn't a font
+/// file DirectWrite can read.
+///
+/// Needs Windows 10 Creators Update (1703) or above.
+#[pyfunction(face_index = "0")]
+#[pyo3(text_signature = "(path, face_index=0)")]
+pub fn open_font(path: PathBuf, face_index: u32, py: Python<'_>) -> PyResult<FontVariant> {
+    open_font_with_simulations(path, face_index, DWRITE_FONT_SIMULATIONS_NONE, py)
+}
+
+/// Recreate a pickled :class:`FontVariant` from the path, face index and simulation names returned by its
+/// ``__getstate__``
+#[pyfunction]
+#[pyo3(name = "_open_pickled")]
+pub fn open_pickled(
+    path: PathBuf,
+    face_index: u32,
+    simulations: Vec<String>,
+    py: Python<'_>,
+) -> PyResult<FontVariant> {
+    let mut flags = DWRITE_FONT_SIMULATIONS_NONE.0;
+    for name in &simulations {
+        flags |= match name.as_str() {
+            "bold" => DWRITE_FONT_SIMULATIONS_BOLD.0,
+            "oblique" => DWRITE_FONT_SIMULATIONS_OBLIQUE.0,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown simulation {:?}",
+                    name
+                )))
+            }
+        };
+    }
+    open_font_with_simulations(path, face_index, DWRITE_FONT_SIMULATIONS(flags), py)
+}
+
+/// Open the face at `face_index` of the font file at `path` like `open_font`, with `simulations` applied
+pub(crate) fn open_font_with_simulations(
+    path: PathBuf,
+    face_index: u32,
+    simulations: DWRITE_FONT_SIMULATIONS,
+    py: Python<'_>,
+) -> PyResult<FontVariant> {
+    let builder = FontSetBuilder::__new__()?;
+    let wide_path = HSTRING::from(path.to_string_lossy().as_ref());
+    let font = unsafe {
+        let file = builder
+            .factory
+            .CreateFontFileReference(&wide_path, None)
+            .map_err(WindowsFontError::from)?;
+
+        let mut supported = BOOL(0);
+        let mut file_type = DWRITE_FONT_FILE_TYPE_UNKNOWN;
+        let mut face_count = 0;
+        file.Analyze(&mut supported, &mut file_type, None, &mut face_count)
+            .map_err(WindowsFontError::from)?;
+        if !supported.as_bool() {
+            return Err(PyValueError::new_err(format!(
+                "{} is not a supported font file",
+                path.display()
+            )));
+        }
+        if face_index >= face_count {
+            return Err(PyIndexError::new_err(format!(
+                "face index {} out of range, {} has {} faces",
+                face_index,
+                path.display(),
+                face_count
+            )));
+        }
+
+        let face_ref = builder
+            .factory
+            .CreateFontFaceReference(&file, face_index, simulations)
+            .map_err(WindowsFontError::from)?;
+        builder
+            .builder
+            .AddFontFaceReference2(&face_ref)
+            .map_err(WindowsFontError::from)?;
+        // The collection only has the one face in it
+        builder
+            .build()?
+            .collection
+            .GetFontFamily(0)
+            .and_then(|family| family.GetFont(0))
+            .map_err(WindowsFontError::from)?
+    };
+    let ifamily = unsafe { font.GetFontFamily() }.map_err(WindowsFontError::from)?;
+
+    Ok(FontVariant::new(
+        Rc::new(font),
+        Py::new(py, FontFamily::new(ifamily))?,
+    ))
+}
@@ -0,0 +1,90 @@
+use std::sync::OnceLock;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use windows::core::Interface;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::FontCollection;
+
+/// What the installed version of DirectWrite supports, probed once per process
+static CAPABILITIES: OnceLock<Vec<(&'static str, bool)>> = OnceLock::new();
+
+/// Any face from the system font collection, to check which interfaces faces support
+unsafe fn any_system_face() -> anyhow::Result<IDWriteFontFace> {
+    let collection = FontCollection::get_system_font_collection()?;
+    Ok(collection.GetFontFamily(0)?.GetFont(0)?.CreateFontFace()?)
+}
+
+/// Any family from the system font collection, to check which interfaces families support
+unsafe fn any_system_family() -> anyhow::Result<IDWriteFontFamily> {
+    let collection = FontCollection::get_system_font_collection()?;
+    Ok(collection.GetFontFamily(0)?)
+}
+
+unsafe fn probe() -> Vec<(&'static str, bool)> {
+    let factory: Option<IDWriteFactory> = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).ok();
+    let supports = |check: fn(&IDWriteFactory) -> bool| factory.as_ref().is_some_and(check);
+    let face = any_system_face().ok();
+
+    vec![
+        // Matching on design axes (`slant`, `optical_size`, `custom_axes` and so on), which goes through
+        // IDWriteFontFamily2
+        (
+            "axis_matching",
+            any_system_family()
+                .ok()
+                .is_some_and(|family| family.cast::<IDWriteFontFamily2>().is_ok()),
+        ),
+        // Color glyph formats, as reported by `FontVariant.glyph_image_formats`
+        (
+            "color_fonts",
+            face.as_ref()
+                .is_some_and(|face| face.cast::<IDWriteFontFace4>().is_ok()),
+        ),
+        // Design axes of variable fonts, as used by `FontVariant.design_axis_ranges` and friends
+        (
+            "variable_fonts",
+            face.as_ref()
+                .is_some_and(|face| face.cast::<IDWriteFontFace5>().is_ok()),
+        ),
+        // Grouping families by typographic rather than weight-stretch-style name, checked by building the typographic
+        // system collection
+        (
+            "typographic_model",
+            supports(|f| {
+                f.cast::<IDWriteFactory6>().ok().is_some_and(|f| {
+                    f.GetSystemFontCollection3(false, DWRITE_FONT_FAMILY_MODEL_TYPOGRAPHIC)
+                        .is_ok()
+                })
+            }),
+        ),
+        // The system font fallback used by `default_fallback_font`
+        (
+            "font_fallback",
+            supports(|f| f.cast::<IDWriteFactory2>().is_ok()),
+        ),
+    ]
+}
+
+/// Which optional features the installed version of Windows supports, as a ``dict`` of feature name to ``bool``
+///
+/// The keys are:
+///
+/// - ``"axis_matching"``: matching variants on design axes (Windows 10 Build 20348 and above)
+/// - ``"color_fonts"``: :meth:`FontVariant.glyph_image_formats` (Windows 10 1607 and above)
+/// - ``"variable_fonts"``: :meth:`FontVariant.design_axis_ranges` and the other axis methods (Windows 10 1803 and
+///   above)
+/// - ``"typographic_model"``: typographic family lookups (Windows 10 1803 and above)
+/// - ``"font_fallback"``: :func:`default_fallback_font` (Windows 8.1 and above)
+///
+/// This lets callers check up front, rather than catching the error from each method. The checks are only done once.
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+pub fn capabilities(py: Python<'_>) -> PyResult<&PyDict> {
+    let dict = PyDict::new(py);
+    for (name, supported) in CAPABILITIES.get_or_init(|| unsafe { probe() }) {
+        dict.set_item(name, supported)?;
+    }
+    Ok(dict)
+}
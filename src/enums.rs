@@ -1,10 +1,10 @@
 use pyo3::prelude::*;
 use windows::Win32::Graphics::DirectWrite::{
-    DWRITE_FONT_STYLE_ITALIC, DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_STYLE_OBLIQUE,
-    DWRITE_FONT_WEIGHT_BLACK, DWRITE_FONT_WEIGHT_BOLD, DWRITE_FONT_WEIGHT_EXTRA_BLACK,
-    DWRITE_FONT_WEIGHT_EXTRA_BOLD, DWRITE_FONT_WEIGHT_LIGHT, DWRITE_FONT_WEIGHT_MEDIUM,
-    DWRITE_FONT_WEIGHT_REGULAR, DWRITE_FONT_WEIGHT_SEMI_BOLD, DWRITE_FONT_WEIGHT_SEMI_LIGHT,
-    DWRITE_FONT_WEIGHT_ULTRA_LIGHT,
+    DWRITE_FONT_STYLE, DWRITE_FONT_STYLE_ITALIC, DWRITE_FONT_STYLE_NORMAL,
+    DWRITE_FONT_STYLE_OBLIQUE, DWRITE_FONT_WEIGHT_BLACK, DWRITE_FONT_WEIGHT_BOLD,
+    DWRITE_FONT_WEIGHT_EXTRA_BLACK, DWRITE_FONT_WEIGHT_EXTRA_BOLD, DWRITE_FONT_WEIGHT_LIGHT,
+    DWRITE_FONT_WEIGHT_MEDIUM, DWRITE_FONT_WEIGHT_REGULAR, DWRITE_FONT_WEIGHT_SEMI_BOLD,
+    DWRITE_FONT_WEIGHT_SEMI_LIGHT, DWRITE_FONT_WEIGHT_ULTRA_LIGHT,
 };
 
 #[pyclass]
@@ -24,6 +24,54 @@ pub enum Weight {
     EXTRA_BLACK = DWRITE_FONT_WEIGHT_EXTRA_BLACK.0,
 }
 
+impl Weight {
+    const ALL: [Weight; 10] = [
+        Weight::ULTRA_LIGHT,
+        Weight::LIGHT,
+        Weight::SEMI_LIGHT,
+        Weight::REGULAR,
+        Weight::MEDIUM,
+        Weight::SEMI_BOLD,
+        Weight::BOLD,
+        Weight::EXTRA_BOLD,
+        Weight::BLACK,
+        Weight::EXTRA_BLACK,
+    ];
+
+    /// The named weight with exactly this value, if there is one
+    pub fn from_value(value: i32) -> Option<Weight> {
+        Self::ALL
+            .iter()
+            .find(|w| (*w).clone() as i32 == value)
+            .cloned()
+    }
+
+    /// The named weight closest to this value. Ties go to the lighter weight
+    pub fn nearest(value: i32) -> Weight {
+        Self::ALL
+            .iter()
+            .min_by_key(|w| ((*w).clone() as i32 - value).abs())
+            .cloned()
+            .unwrap_or(Weight::REGULAR)
+    }
+
+    /// The name of this member, as seen from Python
+    pub fn name(&self) -> &'static str {
+        match self {
+            Weight::ULTRA_LIGHT => "ULTRA_LIGHT",
+            Weight::LIGHT => "LIGHT",
+            Weight::SEMI_LIGHT => "SEMI_LIGHT",
+            Weight::REGULAR => "REGULAR",
+            Weight::MEDIUM => "MEDIUM",
+            Weight::SEMI_BOLD => "SEMI_BOLD",
+            Weight::BOLD => "BOLD",
+            Weight::EXTRA_BOLD => "EXTRA_BOLD",
+            Weight::BLACK => "BLACK",
+            Weight::EXTRA_BLACK => "EXTRA_BLACK",
+        }
+    }
+}
+
 impl From<Weight> for f32 {
     fn from(w: Weight) -> Self {
         w as i32 as f32
@@ -39,3 +87,30 @@ pub enum Style {
     ITALIIC = DWRITE_FONT_STYLE_ITALIC.0,
     OBLIQUE = DWRITE_FONT_STYLE_OBLIQUE.0,
 }
+
+impl From<DWRITE_FONT_STYLE> for Style {
+    fn from(s: DWRITE_FONT_STYLE) -> Self {
+        match s {
+            DWRITE_FONT_STYLE_OBLIQUE => Style::OBLIQUE,
+            DWRITE_FONT_STYLE_ITALIC => Style::ITALIIC,
+            // DirectWrite only defines the three styles, so this should never happen, but don't crash if it does
+            _ => Style::NORMAL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_weight() {
+        assert!(Weight::nearest(400) == Weight::REGULAR);
+        assert!(Weight::nearest(420) == Weight::REGULAR);
+        assert!(Weight::nearest(380) == Weight::REGULAR);
+        assert!(Weight::nearest(1) == Weight::ULTRA_LIGHT);
+        assert!(Weight::nearest(999) == Weight::EXTRA_BLACK);
+        // Halfway between SEMI_LIGHT (350) and REGULAR (400)
+        assert!(Weight::nearest(375) == Weight::SEMI_LIGHT);
+    }
+}
@@ -4,6 +4,23 @@ use pyo3::{
 };
 use thiserror::Error;
 
+pub use read_error::FontReadError;
+
+// pyo3's macro checks a `cfg` that newer compilers don't know about
+#[allow(unexpected_cfgs)]
+mod read_error {
+    use pyo3::{create_exception, exceptions::PyFileNotFoundError};
+
+    create_exception!(
+        windows_fonts,
+        FontReadError,
+        PyFileNotFoundError,
+        "Raised when a variant's font file can't be read from disk, either because it no longer exists or because the \
+         variant was loaded from memory.\n\nThis is a :class:`FileNotFoundError`, so code that already handles that keeps \
+         working."
+    );
+}
+
 #[derive(Error, Debug)]
 pub enum WindowsFontError {
     #[error(transparent)]
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use windows::core::Vtable;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::errors::WindowsFontError;
+use crate::metrics::FontMetrics;
+use crate::tables::{self, FontTable};
+use crate::{font_features, outline, render};
+
+/// A font face opened from a :class:`FontVariant`, for doing many glyph or table level operations on one font
+///
+/// The face is created once, rather than on every call as the equivalent :class:`FontVariant` methods do. Create one
+/// with :meth:`FontVariant.open_face`.
+#[pyclass(module = "windows_fonts", unsendable)]
+pub struct FontFace {
+    face: IDWriteFontFace,
+}
+
+impl FontFace {
+    pub fn new(face: IDWriteFontFace) -> Self {
+        FontFace { face }
+    }
+}
+
+#[pymethods]
+impl FontFace {
+    /// The design metrics of this face, in font design units
+    #[getter]
+    pub fn metrics(&self) -> FontMetrics {
+        let mut metrics = DWRITE_FONT_METRICS::default();
+        unsafe { self.face.GetMetrics(&mut metrics) };
+        metrics.into()
+    }
+
+    /// The index of this face within its font file. Only non-zero for collection (`.ttc`) files
+    #[getter]
+    pub fn face_index(&self) -> u32 {
+        unsafe { self.face.GetIndex() }
+    }
+
+    /// The address of the underlying ``IDWriteFontFace``, for passing to other code using DirectWrite directly
+    ///
+    /// No reference is added, so the pointer is only valid while this :class:`FontFace` is alive. This is the
+    /// counterpart of :func:`variant_from_face`.
+    #[getter]
+    pub fn raw_face_ptr(&self) -> usize {
+        self.face.as_raw() as usize
+    }
+
+    /// The glyph index for each character of `text`. Characters the font doesn't have map to glyph 0 (``.notdef``)
+    #[pyo3(text_signature = "($self, text)")]
+    pub fn glyph_indices(&self, text: &str) -> PyResult<Vec<u16>> {
+        Ok(unsafe { render::glyph_indices(&self.face, text) }.map_err(WindowsFontError::from)?)
+    }
+
+    /// The advance width in pixels of each character of `text` at a font size of `size_px` pixels per em
+    ///
+    /// See :meth:`FontVariant.glyph_advances`.
+    #[pyo3(text_signature = "($self, text, size_px)")]
+    pub fn glyph_advances(&self, text: &str, size_px: f32) -> PyResult<Vec<f32>> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        let scale = size_px / self.metrics().design_units_per_em as f32;
+        let advances = unsafe {
+            render::glyph_indices(&self.face, text)
+                .and_then(|glyphs| render::design_advances(&self.face, &glyphs))
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(advances.iter().map(|&a| a as f32 * scale).collect())
+    }
+
+    /// The total advance width in pixels of `text` at a font size of `size_px` pixels per em
+    ///
+    /// See :meth:`FontVariant.glyph_run_width`.
+    #[pyo3(text_signature = "($self, text, size_px, shaped=False, features=None)")]
+    #[args(shaped = "false", features = "None")]
+    pub fn glyph_run_width(
+        &self,
+        text: &str,
+        size_px: f32,
+        shaped: bool,
+        features: Option<HashMap<String, u32>>,
+    ) -> PyResult<f32> {
+        if !shaped && features.is_none() {
+            return Ok(self.glyph_advances(text, size_px)?.iter().sum());
+        }
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        let features = font_features(features)?;
+        let shaped = unsafe { render::shape(&self.face, text, size_px, &features) }
+            .map_err(WindowsFontError::from)?;
+        Ok(shaped.advances.iter().sum())
+    }
+
+    /// The raw bytes of the OpenType table `tag` (such as ``"head"``), or `None` if the font doesn't have it
+    #[pyo3(text_signature = "($self, tag)")]
+    pub fn get_table(&self, tag: &str, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let tag = tables::tag_from_str(tag)
+            .ok_or_else(|| PyValueError::new_err(format!("{tag:?} is not a four character tag")))?;
+        let table = unsafe { FontTable::get(&self.face, tag) }.map_err(WindowsFontError::from)?;
+        Ok(table.map(|t| PyBytes::new(py, t.data()).into()))
+    }
+
+    /// The outline of the glyph for `char` as an SVG ``<path>`` element
+    ///
+    /// See :meth:`FontVariant.outline_svg`.
+    #[pyo3(text_signature = "($self, char, size=1.0)")]
+    #[args(size = "1.0")]
+    pub fn outline_svg(&self, char: char, size: f32) -> PyResult<String> {
+        if size <= 0.0 {
+            return Err(PyValueError::new_err("size must be positive"));
+        }
+        let metrics = self.metrics();
+        let ascent = metrics.ascent as f32 / metrics.design_units_per_em as f32;
+
+        let path = unsafe {
+            self.face
+                .GetGlyphIndices(&(char as u32), 1)
+                .and_then(|glyph| outline::glyph_svg_path(&self.face, glyph, size, ascent))
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(format!("<path d=\"{path}\"/>"))
+    }
+}
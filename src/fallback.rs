@@ -0,0 +1,105 @@
+use std::rc::Rc;
+
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use windows::core::PCWSTR;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::analysis::TextSource;
+use crate::errors::WindowsFontError;
+use crate::{FontCollection, FontFamily, FontVariant};
+
+// Families to use when font fallback doesn't find anything, in order of preference
+const LAST_RESORT_FAMILIES: [&str; 3] = ["Segoe UI Symbol", "Segoe UI", "Arial"];
+
+/// The font the system font fallback picks to display `c` when no particular font was asked for, or `None` if no
+/// installed font has it
+///
+/// Needs Windows 8.1 or above.
+pub unsafe fn map_character(c: char) -> windows::core::Result<Option<IDWriteFont>> {
+    let factory: IDWriteFactory2 = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
+    let fallback = factory.GetSystemFontFallback()?;
+
+    let text: Vec<u16> = c.encode_utf16(&mut [0; 2]).to_vec();
+    let len = text.len() as u32;
+    let source = TextSource::create(text);
+
+    let mut mapped_length = 0;
+    let mut font: Option<IDWriteFont> = None;
+    let mut scale = 1.0;
+    fallback.MapCharacters(
+        &source,
+        0,
+        len,
+        None::<&IDWriteFontCollection>,
+        PCWSTR::null(),
+        DWRITE_FONT_WEIGHT_NORMAL,
+        DWRITE_FONT_STYLE_NORMAL,
+        DWRITE_FONT_STRETCH_NORMAL,
+        &mut mapped_length,
+        Some(&mut font),
+        &mut scale,
+    )?;
+    Ok(font)
+}
+
+/// A font that is always available to draw a placeholder for characters nothing else can display
+///
+/// With `codepoint`, this is the font the system's font fallback would use for that character. Without, or if no
+/// installed font has the character, it is the font fallback picks for U+FFFD REPLACEMENT CHARACTER, and failing that
+/// the first installed family of Segoe UI Symbol, Segoe UI and Arial.
+///
+/// Needs Windows 8.1 or above.
+#[pyfunction]
+#[pyo3(text_signature = "(codepoint=None)")]
+pub fn default_fallback_font(codepoint: Option<u32>, py: Python<'_>) -> PyResult<FontVariant> {
+    let wanted = match codepoint {
+        Some(cp) => Some(char::from_u32(cp).ok_or_else(|| {
+            PyValueError::new_err(format!("{cp:#x} is not a valid Unicode code point"))
+        })?),
+        None => None,
+    };
+
+    let mut font = None;
+    for c in wanted
+        .iter()
+        .chain(std::iter::once(&char::REPLACEMENT_CHARACTER))
+    {
+        font = unsafe { map_character(*c) }.map_err(WindowsFontError::from)?;
+        if font.is_some() {
+            break;
+        }
+    }
+
+    let font = match font {
+        Some(font) => font,
+        None => unsafe {
+            let collection =
+                FontCollection::get_system_font_collection().map_err(WindowsFontError::from)?;
+            let mut found = None;
+            for name in LAST_RESORT_FAMILIES.iter() {
+                if let Some(family) = FontCollection::_find_family(&collection, name)
+                    .map_err(WindowsFontError::from)?
+                {
+                    found = Some(
+                        family
+                            .GetFirstMatchingFont(
+                                DWRITE_FONT_WEIGHT_NORMAL,
+                                DWRITE_FONT_STRETCH_NORMAL,
+                                DWRITE_FONT_STYLE_NORMAL,
+                            )
+                            .map_err(WindowsFontError::from)?,
+                    );
+                    break;
+                }
+            }
+            found.ok_or_else(|| PyOSError::new_err("no fallback font is installed"))?
+        },
+    };
+    let ifamily = unsafe { font.GetFontFamily() }.map_err(WindowsFontError::from)?;
+
+    Ok(FontVariant::new(
+        Rc::new(font),
+        Py::new(py, FontFamily::new(ifamily))?,
+    ))
+}
@@ -0,0 +1,182 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use windows::core::{Interface, HSTRING};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::errors::WindowsFontError;
+use crate::{FontCollection, FontFamily};
+
+/// The CSS generic family keywords, in the order `families_by_generic` lists them in errors
+const GENERICS: [&str; 5] = ["serif", "sans-serif", "monospace", "cursive", "fantasy"];
+
+/// Families that browsers on Windows use for each generic family, in order of preference
+fn preferred_families(generic: &str) -> &'static [&'static str] {
+    match generic {
+        "serif" => &["Times New Roman", "Georgia", "Cambria", "Constantia"],
+        "sans-serif" => &["Arial", "Segoe UI", "Calibri", "Verdana", "Tahoma"],
+        "monospace" => &["Consolas", "Courier New", "Cascadia Mono", "Lucida Console"],
+        "cursive" => &["Comic Sans MS", "Segoe Script", "Segoe Print", "Gabriola"],
+        "fantasy" => &["Impact", "Jokerman", "Showcard Gothic"],
+        _ => &[],
+    }
+}
+
+/// The generic family a font belongs to, from its PANOSE classification and whether it is monospaced, or `None` if
+/// the classification doesn't say
+fn classify(panose: &[u8; 10], monospaced: bool) -> Option<&'static str> {
+    // PANOSE is a family kind followed by nine digits whose meaning depends on the kind. For text fonts, the second is
+    // the serif style and the fourth the proportion
+    const FAMILY_TEXT_DISPLAY: u8 = DWRITE_PANOSE_FAMILY_TEXT_DISPLAY.0 as u8;
+    const FAMILY_SCRIPT: u8 = DWRITE_PANOSE_FAMILY_SCRIPT.0 as u8;
+    const FAMILY_DECORATIVE: u8 = DWRITE_PANOSE_FAMILY_DECORATIVE.0 as u8;
+    const SERIF_COVE: u8 = DWRITE_PANOSE_SERIF_STYLE_COVE.0 as u8;
+    const SERIF_TRIANGLE: u8 = DWRITE_PANOSE_SERIF_STYLE_TRIANGLE.0 as u8;
+    const SERIF_NORMAL_SANS: u8 = DWRITE_PANOSE_SERIF_STYLE_NORMAL_SANS.0 as u8;
+    const SERIF_ROUNDED: u8 = DWRITE_PANOSE_SERIF_STYLE_ROUNDED.0 as u8;
+    const PROPORTION_MONOSPACED: u8 = DWRITE_PANOSE_PROPORTION_MONOSPACED.0 as u8;
+
+    match panose[0] {
+        FAMILY_TEXT_DISPLAY if monospaced || panose[3] == PROPORTION_MONOSPACED => {
+            Some("monospace")
+        }
+        FAMILY_TEXT_DISPLAY => match panose[1] {
+            SERIF_COVE..=SERIF_TRIANGLE => Some("serif"),
+            SERIF_NORMAL_SANS..=SERIF_ROUNDED => Some("sans-serif"),
+            _ => None,
+        },
+        FAMILY_SCRIPT => Some("cursive"),
+        FAMILY_DECORATIVE => Some("fantasy"),
+        // Fonts that don't fill in PANOSE (which is common) can still be known to be monospaced
+        _ if monospaced => Some("monospace"),
+        _ => None,
+    }
+}
+
+/// The generic family of the regular variant of `family`, or `None` if it can't be told or is a symbol font
+unsafe fn family_generic(
+    family: &IDWriteFontFamily,
+) -> windows::core::Result<Option<&'static str>> {
+    let font = family.GetFirstMatchingFont(
+        DWRITE_FONT_WEIGHT_NORMAL,
+        DWRITE_FONT_STRETCH_NORMAL,
+        DWRITE_FONT_STYLE_NORMAL,
+    )?;
+    if font.IsSymbolFont().as_bool() {
+        return Ok(None);
+    }
+    // PANOSE and the monospaced flag both need IDWriteFont1 (Windows 8)
+    let font1 = match font.cast::<IDWriteFont1>() {
+        Ok(font1) => font1,
+        Err(_) => return Ok(None),
+    };
+    let mut panose = DWRITE_PANOSE::default();
+    font1.GetPanose(&mut panose);
+    Ok(classify(&panose.values, font1.IsMonospacedFont().as_bool()))
+}
+
+/// The installed families for the CSS generic family `generic`, one of ``"serif"``, ``"sans-serif"``,
+/// ``"monospace"``, ``"cursive"`` and ``"fantasy"``, best match first
+///
+/// The list starts with the families browsers on Windows use for that generic family (such as Times New Roman for
+/// ``"serif"``) that are installed, followed by every other family whose PANOSE classification (or, for
+/// ``"monospace"``, whether it is monospaced) puts it in that generic family. Many fonts don't fill in their PANOSE
+/// classification, so the second part is not exhaustive. Symbol fonts are never included.
+///
+/// Pass `collection` to look in that :class:`FontCollection` instead of the installed fonts.
+#[pyfunction("*", collection = "None")]
+#[pyo3(text_signature = "(generic, *, collection=None)")]
+pub fn families_by_generic(
+    generic: &str,
+    collection: Option<&FontCollection>,
+    py: Python<'_>,
+) -> PyResult<Vec<Py<FontFamily>>> {
+    if !GENERICS.contains(&generic) {
+        return Err(PyValueError::new_err(format!(
+            "generic must be one of {}, not {generic:?}",
+            GENERICS.join(", ")
+        )));
+    }
+    let collection: IDWriteFontCollection = match collection {
+        Some(collection) => collection.collection.cast(),
+        None => FontCollection::get_system_font_collection()
+            .map_err(WindowsFontError::from)?
+            .cast(),
+    }
+    .map_err(WindowsFontError::from)?;
+
+    let families = unsafe {
+        (|| -> windows::core::Result<Vec<IDWriteFontFamily>> {
+            // The preferred families that are installed come first, then the others in collection order
+            let mut indices = Vec::new();
+            for name in preferred_families(generic) {
+                let mut index = 0;
+                let mut exists = BOOL(0);
+                collection.FindFamilyName(&HSTRING::from(*name), &mut index, &mut exists)?;
+                if exists.as_bool() {
+                    indices.push(index);
+                }
+            }
+            let mut families = indices
+                .iter()
+                .map(|&index| collection.GetFontFamily(index))
+                .collect::<windows::core::Result<Vec<_>>>()?;
+            for index in 0..collection.GetFontFamilyCount() {
+                if indices.contains(&index) {
+                    continue;
+                }
+                let family = collection.GetFontFamily(index)?;
+                if family_generic(&family)? == Some(generic) {
+                    families.push(family);
+                }
+            }
+            Ok(families)
+        })()
+        .map_err(WindowsFontError::from)?
+    };
+
+    families
+        .into_iter()
+        .map(|family| Py::new(py, FontFamily::new(family)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        // Times New Roman, Arial and Courier New
+        assert_eq!(
+            classify(&[2, 2, 6, 3, 5, 4, 5, 2, 3, 4], false),
+            Some("serif")
+        );
+        assert_eq!(
+            classify(&[2, 11, 6, 4, 2, 2, 2, 2, 2, 4], false),
+            Some("sans-serif")
+        );
+        assert_eq!(
+            classify(&[2, 7, 3, 9, 2, 2, 5, 2, 4, 4], true),
+            Some("monospace")
+        );
+        // Monospaced without the flag, and the flag without PANOSE
+        assert_eq!(
+            classify(&[2, 11, 6, 9, 2, 2, 2, 2, 2, 4], false),
+            Some("monospace")
+        );
+        assert_eq!(classify(&[0; 10], true), Some("monospace"));
+
+        assert_eq!(
+            classify(&[3, 15, 7, 2, 3, 3, 2, 2, 2, 4], false),
+            Some("cursive")
+        );
+        assert_eq!(
+            classify(&[4, 2, 9, 5, 2, 2, 2, 2, 2, 4], false),
+            Some("fantasy")
+        );
+        assert_eq!(classify(&[5, 5, 1, 2, 1, 7, 6, 2, 5, 7], false), None);
+        assert_eq!(classify(&[0; 10], false), None);
+        assert_eq!(classify(&[2, 0, 0, 0, 0, 0, 0, 0, 0, 0], false), None);
+    }
+}
@@ -1,6 +1,8 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cell::{OnceCell, RefCell};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::ffi::{c_int, c_void};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::slice::{self};
 
@@ -9,22 +11,26 @@ use anyhow::{bail, Context, Result};
 use ::phf::{phf_map, Map};
 use pyo3::class::basic::CompareOp;
 use pyo3::exceptions::{
-    PyIndexError, PyKeyError, PyOSError, PyRuntimeError, PyTypeError, PyValueError,
+    PyIndexError, PyKeyError, PyOSError, PyPermissionError, PyRuntimeError, PyTypeError,
+    PyValueError,
 };
+use pyo3::once_cell::GILOnceCell;
 use pyo3::prelude::*;
-use pyo3::types::{PyList, PyLong, PyString, PyTuple};
+use pyo3::types::{PyBytes, PyDict, PyFrozenSet, PyList, PyLong, PyString, PyType};
 use windows::core::HSTRING;
-use windows::Win32::Foundation::BOOL;
+use windows::Win32::Foundation::{BOOL, DWRITE_E_NOCOLOR, DWRITE_E_NOFONT};
 
 use windows::Win32::Graphics::DirectWrite::*;
 use windows::{
-    core::{Interface, PCWSTR},
+    core::{IUnknown, Interface, Vtable, PCWSTR},
     w,
 };
 
 thread_local! {
-    static LOCAL_LOADER: RefCell<IDWriteLocalFontFileLoader> = RefCell::new(_get_local_loader().unwrap());
-    static USER_LOCALE: HSTRING = _get_user_locale().unwrap();
+    // `None` once `shutdown()` has been called
+    static LOCAL_LOADER: RefCell<Option<IDWriteLocalFontFileLoader>> = RefCell::new(Some(_get_local_loader().unwrap()));
+    // Replaced by `reload_locale()`
+    static USER_LOCALE: RefCell<HSTRING> = RefCell::new(_get_user_locale().unwrap());
 }
 
 fn _get_local_loader() -> Result<IDWriteLocalFontFileLoader> {
@@ -66,10 +72,30 @@ fn _get_user_locale() -> Result<HSTRING> {
     }
 }
 
+mod analysis;
+mod brotli;
+mod buffer;
+mod builder;
+mod capabilities;
 mod enums;
 mod errors;
+mod face;
+mod fallback;
+mod generic;
+mod metrics;
+mod outline;
+mod png;
+mod preview;
+mod render;
+mod snapshot;
+mod subset;
+mod system;
+mod tables;
+mod woff2;
 
-use errors::WindowsFontError;
+use errors::{FontReadError, WindowsFontError};
+use metrics::{ExtendedMetrics, FontMetrics, ScaledMetrics, VerticalMetrics};
+use tables::FontTable;
 
 #[derive(FromPyObject, Debug)]
 enum IntOrStr<'a> {
@@ -80,6 +106,8 @@ enum IntOrStr<'a> {
 #[pyclass(module = "windows_fonts", unsendable)]
 struct FontCollection {
     collection: IDWriteFontCollection1,
+    // Every instance of the system collection compares equal, other collections compare by identity
+    is_system: bool,
 }
 
 impl FontCollection {
@@ -96,15 +124,96 @@ impl FontCollection {
             )
         }
     }
+
+    /// The families of the fonts in this collection whose `id` property is `value`, in font set order. A family is
+    /// included once for each matching font
+    unsafe fn _families_with_property(
+        &self,
+        id: DWRITE_FONT_PROPERTY_ID,
+        value: &str,
+    ) -> windows::core::Result<Vec<IDWriteFontFamily>> {
+        let value = HSTRING::from(value);
+        let property = DWRITE_FONT_PROPERTY {
+            propertyId: id,
+            propertyValue: PCWSTR(value.as_ptr()),
+            ..Default::default()
+        };
+        let matching = self
+            .collection
+            .GetFontSet()?
+            .GetMatchingFonts2(&[property])?;
+        (0..matching.GetFontCount())
+            .map(|i| {
+                matching
+                    .GetFontFaceReference(i)
+                    .and_then(|font_ref| font_ref.CreateFontFace())
+                    .and_then(|face| self.collection.GetFontFromFontFace(&face))
+                    .and_then(|font| font.GetFontFamily())
+            })
+            .collect()
+    }
+
+    /// Up to three family names that are a small edit away from `name`, closest first, for suggesting when a lookup
+    /// fails
+    unsafe fn _similar_family_names(&self, name: &str) -> Result<Vec<String>> {
+        // Allow roughly one typo for every three characters
+        let max_distance = (name.chars().count() / 3).max(1);
+        let mut similar: Vec<(usize, String)> = self
+            ._family_names()?
+            .into_iter()
+            .map(|family_name| (edit_distance(name, &family_name), family_name))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect();
+        similar.sort();
+        Ok(similar.into_iter().take(3).map(|(_, name)| name).collect())
+    }
+
+    /// The best name of every family in this collection
+    unsafe fn _family_names(&self) -> Result<BTreeSet<String>> {
+        (0..self.collection.GetFontFamilyCount())
+            .map(|i| {
+                self.collection
+                    .GetFontFamily(i)?
+                    .GetFamilyNames()?
+                    .get_best_name()
+            })
+            .collect()
+    }
+
+    /// Look up a family by name in the given collection
+    unsafe fn _find_family<C: Interface>(
+        collection: &C,
+        name: &str,
+    ) -> windows::core::Result<Option<IDWriteFontFamily>> {
+        let collection: IDWriteFontCollection = collection.cast()?;
+        let mut exists = BOOL(0);
+        let mut index = 0;
+        collection.FindFamilyName(&HSTRING::from(name), &mut index, &mut exists)?;
+        if !exists.as_bool() {
+            return Ok(None);
+        }
+        collection.GetFontFamily(index).map(Some)
+    }
+}
+
+/// A single property value, or several alternatives for the same property
+#[derive(FromPyObject)]
+enum PropertyValues<'a> {
+    One(&'a str),
+    Many(Vec<&'a str>),
 }
 
-#[pyfunction(kwargs = "**")]
-fn get_matching_variants(kwargs: Option<HashMap<&str, &str>>) -> PyResult<Vec<FontVariant>> {
+fn _get_property_filters(
+    kwargs: Option<HashMap<&str, PropertyValues>>,
+) -> PyResult<(Vec<DWRITE_FONT_PROPERTY>, Vec<HSTRING>)> {
     let kwargs = match kwargs {
         Some(val) => val,
         None => return Err(PyTypeError::new_err("no filter conditions passed")),
     };
     let mut filters = Vec::<DWRITE_FONT_PROPERTY>::with_capacity(kwargs.len());
+    // The DWRITE_FONT_PROPERTY only holds a pointer to the value, so we need to keep the strings alive for as long as
+    // the filters are in use
+    let mut values = Vec::<HSTRING>::with_capacity(kwargs.len());
     for (name, val) in kwargs {
         match INFO_STRING_NAMES.get(name) {
             Some((_, DWRITE_FONT_PROPERTY_ID_NONE)) => {
@@ -113,11 +222,25 @@ fn get_matching_variants(kwargs: Option<HashMap<&str, &str>>) -> PyResult<Vec<Fo
                 )))
             }
             Some((_, id)) => {
-                filters.push(DWRITE_FONT_PROPERTY {
-                    propertyId: *id,
-                    propertyValue: PCWSTR(HSTRING::from(val).as_ptr()),
-                    ..Default::default()
-                });
+                // Multiple properties with the same id are OR'd together by DirectWrite
+                let vals = match val {
+                    PropertyValues::One(val) => vec![val],
+                    PropertyValues::Many(vals) if vals.is_empty() => {
+                        return Err(PyValueError::new_err(format!(
+                            "{name:?} must have at least one value"
+                        )))
+                    }
+                    PropertyValues::Many(vals) => vals,
+                };
+                for val in vals {
+                    let val = HSTRING::from(val);
+                    filters.push(DWRITE_FONT_PROPERTY {
+                        propertyId: *id,
+                        propertyValue: PCWSTR(val.as_ptr()),
+                        ..Default::default()
+                    });
+                    values.push(val);
+                }
             }
             _ => {
                 return Err(PyTypeError::new_err(format!(
@@ -126,43 +249,179 @@ fn get_matching_variants(kwargs: Option<HashMap<&str, &str>>) -> PyResult<Vec<Fo
             }
         };
     }
+    Ok((filters, values))
+}
 
-    // This is a  closure so we can apply map_err to the whole block of Win API calls
-    let win_api_block = || unsafe {
-        let factory: IDWriteFactory3 = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
-
-        let fontset = factory.GetSystemFontSet()?;
+unsafe fn _get_matching_variants_iter(
+    filters: &[DWRITE_FONT_PROPERTY],
+    collection: Option<&FontCollection>,
+) -> windows::core::Result<ResultFontVariantIter> {
+    let (fontset, collection) = match collection {
+        Some(collection) => (
+            collection.collection.GetFontSet()?,
+            collection.collection.clone(),
+        ),
+        None => {
+            let factory: IDWriteFactory3 = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
 
-        let set = fontset.GetMatchingFonts2(&filters)?;
+            let mut collection: Option<IDWriteFontCollection1> = None;
+            factory.GetSystemFontCollection(&mut collection as *mut _ as _, false)?;
+            // Panic here is okay, cos we _shouldn't_ have an error but no collection given back
+            let collection = collection
+                .expect("GetSystemFontCollection had not error but gave us no collection");
+            (factory.GetSystemFontSet()?, collection)
+        }
+    };
 
-        let count = set.GetFontCount();
+    let set = fontset.GetMatchingFonts2(filters)?;
 
-        let mut collection: Option<IDWriteFontCollection1> = None;
-        factory.GetSystemFontCollection(&mut collection as *mut _ as _, false)?;
-        // Panic here is okay, cos we _shouldn't_ have an error but no collection given back
-        let collection =
-            collection.expect("GetSystemFontCollection had not error but gave us no collection");
+    let count = set.GetFontCount();
 
-        let mut res = Vec::<FontVariant>::with_capacity(count as usize);
-        for n in 0..count {
+    let iter = (0..count).map(move |n| -> Result<FontVariant> {
+        let font = (|| {
             let font_ref = set.GetFontFaceReference(n)?;
             let face: IDWriteFontFace = font_ref.CreateFontFace()?.cast()?;
-            let font = collection.GetFontFromFontFace(&face)?;
-            let ifamily = font.GetFontFamily()?;
+            collection.GetFontFromFontFace(&face)
+        })()
+        .map_err(WindowsFontError::from)?;
+        let ifamily = font.GetFontFamily().map_err(WindowsFontError::from)?;
 
-            let family = FontFamily(ifamily);
+        let family = Python::with_gil(|py| Py::new(py, FontFamily::new(ifamily)))?;
 
-            let x =
-                Python::with_gil(|py| -> PyResult<Py<FontFamily>> { Py::new(py, family) }).unwrap();
+        Ok(FontVariant::new(Rc::new(font), family))
+    });
+    Ok(Box::new(iter))
+}
 
-            res.push(FontVariant {
-                family: x,
-                font: Rc::new(font),
-            });
-        }
-        Ok::<Vec<FontVariant>, windows::core::Error>(res)
-    };
-    Ok(win_api_block().map_err(WindowsFontError::from)?)
+/// Find all font variants in the system font set matching the given properties
+///
+/// Each property can be given a single value or a list of values, in which case a variant matching any of them is
+/// returned (for example `win32_family_names=["Arial", "Helvetica"]`).
+///
+/// Pass `collection` to search the fonts in that :class:`FontCollection` (such as one built with a
+/// :class:`FontSetBuilder`) instead of the system font set.
+///
+/// Pass `limit` to only get up to that many variants; the variants after those are never created.
+#[pyfunction("*", collection = "None", limit = "None", kwargs = "**")]
+fn get_matching_variants(
+    collection: Option<&FontCollection>,
+    limit: Option<usize>,
+    kwargs: Option<HashMap<&str, PropertyValues>>,
+) -> PyResult<Vec<FontVariant>> {
+    let (filters, _values) = _get_property_filters(kwargs)?;
+
+    let iter = unsafe { _get_matching_variants_iter(&filters, collection) }
+        .map_err(WindowsFontError::from)?
+        .take(limit.unwrap_or(usize::MAX));
+
+    let mut res = Vec::<FontVariant>::with_capacity(iter.size_hint().0);
+    for item in iter {
+        res.push(item?);
+    }
+    Ok(res)
+}
+
+/// Lazy version of :func:`get_matching_variants`
+///
+/// The font set query is performed up front, but each `FontVariant` is only created as it is consumed from the
+/// returned iterator.
+#[pyfunction("*", collection = "None", kwargs = "**")]
+fn iter_matching_variants(
+    collection: Option<&FontCollection>,
+    kwargs: Option<HashMap<&str, PropertyValues>>,
+) -> PyResult<FontVariantIter> {
+    let (filters, _values) = _get_property_filters(kwargs)?;
+
+    let iter = unsafe { _get_matching_variants_iter(&filters, collection) }
+        .map_err(WindowsFontError::from)?;
+    Ok(FontVariantIter { iter })
+}
+
+/// Find all font variants in the system font set with the given weight-stretch-style family name
+#[pyfunction]
+#[pyo3(text_signature = "(name)")]
+fn find_by_wss_family(name: &str) -> PyResult<Vec<FontVariant>> {
+    let mut kwargs = HashMap::new();
+    kwargs.insert(
+        "weight_stretch_style_family_name",
+        PropertyValues::One(name),
+    );
+    get_matching_variants(None, None, Some(kwargs))
+}
+
+/// Release the COM objects this module holds on to for the current thread
+///
+/// Applications embedding Python should call this before ``Py_Finalize`` (and before ``CoUninitialize``), so the
+/// objects aren't released after COM has been torn down. Looking up the file names of fonts on this thread raises an
+/// error after this has been called.
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+fn shutdown() {
+    // `try_with` as the thread local may already have been destroyed if this is called during thread teardown
+    _ = LOCAL_LOADER.try_with(|cell| cell.borrow_mut().take());
+}
+
+/// Look up the user's locale again, after it has been changed while this process is running, and return its name
+/// (such as ``"en-GB"``)
+///
+/// The locale decides which language names are returned in, and is otherwise only looked up once per thread. This
+/// only affects the current thread, and families and variants that have already read their name keep it.
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+fn reload_locale() -> Result<String> {
+    let locale = _get_user_locale()?;
+    let name = locale.to_string_lossy();
+    USER_LOCALE.with(|cell| cell.replace(locale));
+    Ok(name)
+}
+
+/// Do the one-off setup work for the current thread up front, so the first real query is fast
+///
+/// This creates the DirectWrite factory and system font collection, and looks up the user's locale and the loader
+/// used to find font file names. Any errors doing so are raised here, rather than from whichever call happens to
+/// need them first.
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+fn warm_up() -> Result<()> {
+    // Do the fallible work ourselves first so errors are raised rather than panicking in the thread local initialisers
+    _get_local_loader()?;
+    _get_user_locale()?;
+    LOCAL_LOADER.with(|_| ());
+    USER_LOCALE.with(|_| ());
+
+    let factory: IDWriteFactory3 = unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED) }?;
+    unsafe { factory.GetSystemFontSet() }?;
+    FontCollection::get_system_font_collection()?;
+    Ok(())
+}
+
+/// Resolve a raw ``IDWriteFontFace`` pointer (as an integer) back to the matching :class:`FontVariant` in the system
+/// font collection
+///
+/// This is for interop with other code using DirectWrite directly, for instance via ``ctypes`` or ``comtypes``. The
+/// pointer must be a valid ``IDWriteFontFace*``; passing anything else will likely crash the interpreter. A reference
+/// is added to the face while it is in use, so the caller keeps ownership of the pointer it passed in.
+///
+/// Raises ``OSError`` if the face isn't part of the system font collection.
+#[pyfunction]
+#[pyo3(text_signature = "(raw_face_ptr)")]
+fn variant_from_face(raw_face_ptr: usize, py: Python<'_>) -> PyResult<FontVariant> {
+    if raw_face_ptr == 0 {
+        return Err(PyValueError::new_err("raw_face_ptr must not be NULL"));
+    }
+    let raw = raw_face_ptr as *mut c_void;
+    // Borrowing (rather than taking ownership of) the pointer means we don't release the caller's reference
+    let face = unsafe { IDWriteFontFace::from_raw_borrowed(&raw) };
+
+    let collection =
+        FontCollection::get_system_font_collection().map_err(WindowsFontError::from)?;
+    let font = unsafe { collection.GetFontFromFontFace(face) }.map_err(WindowsFontError::from)?;
+    let ifamily = unsafe { font.GetFontFamily() }.map_err(WindowsFontError::from)?;
+
+    Ok(FontVariant::new(
+        Rc::new(font),
+        Py::new(py, FontFamily::new(ifamily))?,
+    ))
 }
 
 #[pymethods]
@@ -170,7 +429,42 @@ impl FontCollection {
     #[new]
     fn __new__() -> Result<Self> {
         let collection = Self::get_system_font_collection()?;
-        Ok(FontCollection { collection })
+        Ok(FontCollection {
+            collection,
+            is_system: true,
+        })
+    }
+
+    /// Create a collection from the fonts added to a :class:`FontSetBuilder`
+    #[classmethod]
+    #[pyo3(text_signature = "(builder)")]
+    fn from_font_set(_cls: &PyType, builder: &builder::FontSetBuilder) -> PyResult<Self> {
+        builder.build()
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => self.eq(other).into_py(py),
+            CompareOp::Ne => self.ne(other).into_py(py),
+            _ => py.NotImplemented(),
+        }
+    }
+
+    fn __hash__(&self) -> isize {
+        if self.is_system {
+            // Any constant will do, as long as it is the same for every system collection
+            0x5359_5354
+        } else {
+            self.collection.as_raw() as isize
+        }
+    }
+
+    /// Iterate over the families from last to first
+    fn __reversed__(&self) -> ReversedFamilyIter {
+        ReversedFamilyIter {
+            collection: self.collection.clone(),
+            remaining: unsafe { self.collection.GetFontFamilyCount() },
+        }
     }
 
     fn __len__(&self) -> usize {
@@ -187,14 +481,53 @@ impl FontCollection {
                     .FindFamilyName(&HSTRING::from_wide(s.as_slice()), &mut i_out, &mut exists)
                     .map_err(WindowsFontError::from)?;
                 if !exists.as_bool() {
-                    return Err(PyKeyError::new_err(format!(
-                        "unknown font family {:?}",
-                        str
-                    )));
+                    // Not a family name, but it might be the name of a font in a family, such as "Arial Narrow" or
+                    // "Arial-BoldMT"
+                    let name = str.to_str()?;
+                    for id in [
+                        DWRITE_FONT_PROPERTY_ID_WIN32_FAMILY_NAME,
+                        DWRITE_FONT_PROPERTY_ID_FULL_NAME,
+                        DWRITE_FONT_PROPERTY_ID_POSTSCRIPT_NAME,
+                    ]
+                    .iter()
+                    {
+                        if let Some(family) = self
+                            ._families_with_property(*id, name)
+                            .map_err(WindowsFontError::from)?
+                            .into_iter()
+                            .next()
+                        {
+                            return Ok(FontFamily::new(family));
+                        }
+                    }
+                    let suggestions = self
+                        ._similar_family_names(name)
+                        .map_err(WindowsFontError::from)?;
+                    let mut message = format!("unknown font family {:?}", str);
+                    if let Some((last, rest)) = suggestions.split_last() {
+                        let quote = |name: &String| format!("'{}'", name);
+                        message.push_str("; did you mean ");
+                        if !rest.is_empty() {
+                            message
+                                .push_str(&rest.iter().map(quote).collect::<Vec<_>>().join(", "));
+                            message.push_str(" or ");
+                        }
+                        message.push_str(&quote(last));
+                        message.push('?');
+                    }
+                    return Err(PyKeyError::new_err(message));
                 }
                 i_out
             },
-            IntOrStr::Int(idx) => idx as u32,
+            IntOrStr::Int(idx) => {
+                let idx = if idx < 0 {
+                    idx + unsafe { self.collection.GetFontFamilyCount() } as isize
+                } else {
+                    idx
+                };
+                // Anything that doesn't fit is out of range, rather than wrapping around to a valid index
+                u32::try_from(idx).map_err(|_| PyIndexError::new_err("list index out of range"))?
+            }
         };
 
         if index >= unsafe { self.collection.GetFontFamilyCount() } {
@@ -207,21 +540,350 @@ impl FontCollection {
                 .map_err(WindowsFontError::from)?
         };
 
-        Ok(FontFamily(ifamily))
+        Ok(FontFamily::new(ifamily))
+    }
+
+    /// Get the family called exactly `name`, matching case and every other character
+    ///
+    /// Unlike ``collection[name]``, which ignores case and falls back to the names of individual fonts, this only
+    /// finds a family that has `name` as one of its family names, character for character. Raises ``KeyError``
+    /// otherwise. This is for validation tools looking for names that differ only in case or have been mangled.
+    #[pyo3(text_signature = "($self, name)")]
+    fn get_exact(&self, name: &str) -> PyResult<FontFamily> {
+        let family = unsafe { Self::_find_family(&self.collection, name) }
+            .map_err(WindowsFontError::from)?;
+        if let Some(family) = family {
+            let names = unsafe {
+                family
+                    .GetFamilyNames()
+                    .map_err(WindowsFontError::from)?
+                    .get_all_strings()?
+            };
+            if names.iter().any(|n| n == name) {
+                return Ok(FontFamily::new(family));
+            }
+        }
+        Err(PyKeyError::new_err(format!(
+            "no font family named exactly {:?}",
+            name
+        )))
+    }
+
+    /// The family the face at `face_index` of the font file at `path` belongs to, or `None` if that font isn't part of
+    /// this collection
+    ///
+    /// The file is looked up by its path, so for the system collection `path` must be where the font is installed,
+    /// such as ``"C:/Windows/Fonts/arial.ttf"``; a copy of the file elsewhere isn't found. Like :func:`open_font`,
+    /// raises ``IndexError`` if the file doesn't have that many faces, and ``ValueError`` if it isn't a font file
+    /// DirectWrite can read.
+    #[pyo3(text_signature = "($self, path, face_index=0)")]
+    #[args(face_index = "0")]
+    fn family_for_file(&self, path: PathBuf, face_index: u32) -> PyResult<Option<FontFamily>> {
+        let wide_path = HSTRING::from(path.to_string_lossy().as_ref());
+        unsafe {
+            let factory: IDWriteFactory =
+                DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).map_err(WindowsFontError::from)?;
+            let file = factory
+                .CreateFontFileReference(&wide_path, None)
+                .map_err(WindowsFontError::from)?;
+
+            let mut supported = BOOL(0);
+            let mut file_type = DWRITE_FONT_FILE_TYPE_UNKNOWN;
+            let mut face_type = DWRITE_FONT_FACE_TYPE_UNKNOWN;
+            let mut face_count = 0;
+            file.Analyze(
+                &mut supported,
+                &mut file_type,
+                Some(&mut face_type),
+                &mut face_count,
+            )
+            .map_err(WindowsFontError::from)?;
+            if !supported.as_bool() {
+                return Err(PyValueError::new_err(format!(
+                    "{} is not a supported font file",
+                    path.display()
+                )));
+            }
+            if face_index >= face_count {
+                return Err(PyIndexError::new_err(format!(
+                    "face index {} out of range, {} has {} faces",
+                    face_index,
+                    path.display(),
+                    face_count
+                )));
+            }
+
+            let face = factory
+                .CreateFontFace(
+                    face_type,
+                    &[Some(file)],
+                    face_index,
+                    DWRITE_FONT_SIMULATIONS_NONE,
+                )
+                .map_err(WindowsFontError::from)?;
+            // Fails with DWRITE_E_NOFONT when the face isn't in the collection
+            match self.collection.GetFontFromFontFace(&face) {
+                Ok(font) => Ok(Some(FontFamily::new(
+                    font.GetFontFamily().map_err(WindowsFontError::from)?,
+                ))),
+                Err(e) if e.code() == DWRITE_E_NOFONT => Ok(None),
+                Err(e) => Err(WindowsFontError::from(e).into()),
+            }
+        }
+    }
+
+    /// The name of the font family at `index`, without creating a :class:`FontFamily`
+    #[pyo3(text_signature = "($self, index)")]
+    fn family_at(&self, index: u32) -> PyResult<String> {
+        if index >= unsafe { self.collection.GetFontFamilyCount() } {
+            return Err(PyIndexError::new_err("list index out of range"));
+        }
+
+        let name = unsafe {
+            let names = self
+                .collection
+                .GetFontFamily(index)
+                .and_then(|family| family.GetFamilyNames())
+                .map_err(WindowsFontError::from)?;
+            names.get_best_name()?
+        };
+        Ok(name)
+    }
+
+    /// A set of the names of every family in this collection, as :attr:`FontFamily.name` gives them
+    ///
+    /// The names are all looked up in one go, without creating any :class:`FontFamily` objects, so this is the
+    /// quickest way to check many names against the collection (for example to filter autocomplete suggestions).
+    #[pyo3(text_signature = "($self)")]
+    fn names_set(&self) -> PyResult<HashSet<String>> {
+        let count = unsafe { self.collection.GetFontFamilyCount() };
+        (0..count).map(|index| self.family_at(index)).collect()
+    }
+
+    /// Iterate over ``(name, family)`` for every family in this collection
+    ///
+    /// The name is the one :attr:`FontFamily.name` gives, and is looked up once and kept by the family, so this is
+    /// cheaper than reading ``family.name`` while iterating over the collection.
+    #[pyo3(text_signature = "($self)")]
+    fn iter_with_names(&self) -> FamilyNameIter {
+        FamilyNameIter {
+            collection: self.collection.clone(),
+            next: 0,
+        }
+    }
+
+    /// A collection of just the fonts matching the given properties, from the system font set or from `collection`
+    ///
+    /// The properties are the same as for :func:`get_matching_variants`, such as
+    /// ``supported_script_language_tag="Cyrl"`` for every font that supports Cyrillic. The result is a normal
+    /// collection, grouped into families like any other, so can be enumerated repeatedly without filtering again.
+    ///
+    /// Needs Windows 10 Creators Update (1703) or above.
+    #[classmethod]
+    #[pyo3(text_signature = "(*, collection=None, **properties)")]
+    #[args("*", collection = "None", kwargs = "**")]
+    fn from_font_set_with_filter(
+        _cls: &PyType,
+        collection: Option<&FontCollection>,
+        kwargs: Option<HashMap<&str, PropertyValues>>,
+    ) -> PyResult<Self> {
+        let (filters, _values) = _get_property_filters(kwargs)?;
+
+        let factory: IDWriteFactory3 = unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED) }
+            .map_err(WindowsFontError::from)?;
+        let factory = factory.cast::<IDWriteFactory5>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 15063 or above".to_owned(),
+            )
+        })?;
+
+        let collection = unsafe {
+            match collection {
+                Some(collection) => collection.collection.GetFontSet(),
+                None => factory.GetSystemFontSet(),
+            }
+            .and_then(|fontset| fontset.GetMatchingFonts2(&filters))
+            .and_then(|set| factory.CreateFontCollectionFromFontSet(&set))
+            .map_err(WindowsFontError::from)?
+        };
+        Ok(FontCollection {
+            collection,
+            is_system: false,
+        })
+    }
+
+    /// A collection of just the installed fonts with the given :attr:`FontVariant.install_scope`: ``"user"`` for
+    /// fonts installed for the current user only, or ``"machine"`` for fonts installed for everyone
+    ///
+    /// Needs Windows 10 Creators Update (1703) or above.
+    #[classmethod]
+    #[pyo3(text_signature = "(scope)")]
+    fn installed_for(_cls: &PyType, scope: &str) -> PyResult<Self> {
+        if scope != "user" && scope != "machine" {
+            return Err(PyValueError::new_err(format!(
+                "scope must be \"user\" or \"machine\", not {scope:?}"
+            )));
+        }
+
+        let factory: IDWriteFactory3 = unsafe { DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED) }
+            .map_err(WindowsFontError::from)?;
+        let factory = factory.cast::<IDWriteFactory5>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 15063 or above".to_owned(),
+            )
+        })?;
+
+        let collection = unsafe {
+            let fontset = factory.GetSystemFontSet().map_err(WindowsFontError::from)?;
+            let builder = factory
+                .CreateFontSetBuilder2()
+                .map_err(WindowsFontError::from)?;
+            for i in 0..fontset.GetFontCount() {
+                let font_ref = fontset
+                    .GetFontFaceReference(i)
+                    .map_err(WindowsFontError::from)?;
+                let file = font_ref.GetFontFile().map_err(WindowsFontError::from)?;
+                // Only fonts on the local disk have a path to check
+                let path = FontVariant::_get_file_path(&file)?;
+                if path.and_then(|path| install_scope_for_path(&path)) == Some(scope) {
+                    builder
+                        .AddFontFaceReference2(&font_ref)
+                        .map_err(WindowsFontError::from)?;
+                }
+            }
+            builder
+                .CreateFontSet()
+                .and_then(|set| factory.CreateFontCollectionFromFontSet(&set))
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(FontCollection {
+            collection,
+            is_system: false,
+        })
+    }
+
+    /// A :class:`FontSnapshot` of every font in this collection
+    ///
+    /// The snapshots hold plain copies of the metadata, so unlike :class:`FontVariant` they can be processed on other
+    /// threads. Building the snapshot reads from every font, so can take a while for the system collection.
+    #[pyo3(text_signature = "($self)")]
+    fn snapshot(&self) -> PyResult<Vec<snapshot::FontSnapshot>> {
+        snapshot::snapshot_collection(&self.collection)
+    }
+
+    /// Compare the family names in this collection with those in `other`, as a tuple of sorted lists
+    /// ``(only_in_self, only_in_other, in_both)``
+    ///
+    /// Useful for checking a custom collection against the system one, for instance to see which of the fonts an
+    /// application ships aren't installed.
+    #[pyo3(text_signature = "($self, other)")]
+    fn diff(&self, other: &FontCollection) -> Result<(Vec<String>, Vec<String>, Vec<String>)> {
+        let ours = unsafe { self._family_names() }?;
+        let theirs = unsafe { other._family_names() }?;
+        Ok((
+            ours.difference(&theirs).cloned().collect(),
+            theirs.difference(&ours).cloned().collect(),
+            ours.intersection(&theirs).cloned().collect(),
+        ))
+    }
+
+    /// The families in this collection for which ``predicate(family)`` is true
+    ///
+    /// Exceptions raised by `predicate` are propagated.
+    #[pyo3(text_signature = "($self, predicate)")]
+    fn filter(&self, predicate: &PyAny, py: Python<'_>) -> PyResult<Vec<Py<FontFamily>>> {
+        let mut families = Vec::new();
+        for i in 0..unsafe { self.collection.GetFontFamilyCount() } {
+            let ifamily =
+                unsafe { self.collection.GetFontFamily(i) }.map_err(WindowsFontError::from)?;
+            let family = Py::new(py, FontFamily::new(ifamily))?;
+            if predicate.call1((family.clone_ref(py),))?.is_true()? {
+                families.push(family);
+            }
+        }
+        Ok(families)
+    }
+
+    /// All the families that `name` could refer to, under any of the ways Windows groups fonts into families
+    ///
+    /// This looks `name` up as a weight-stretch-style family name (the model used by this collection), as a
+    /// typographic family name (Windows 10 Build 18362 and above), and as the Win32 family name of individual fonts
+    /// (so ``"Arial Narrow"`` finds the ``"Arial"`` family that contains it). An empty list is returned if nothing
+    /// matches.
+    #[pyo3(text_signature = "($self, name)")]
+    fn find_all(&self, name: &str) -> PyResult<Vec<FontFamily>> {
+        let mut families: Vec<FontFamily> = Vec::new();
+        let mut push = |family: IDWriteFontFamily| {
+            let family = FontFamily::new(family);
+            if !families.contains(&family) {
+                families.push(family);
+            }
+        };
+
+        unsafe {
+            if let Some(family) =
+                Self::_find_family(&self.collection, name).map_err(WindowsFontError::from)?
+            {
+                push(family);
+            }
+
+            let fontset = self
+                .collection
+                .GetFontSet()
+                .map_err(WindowsFontError::from)?;
+
+            let factory: IDWriteFactory3 =
+                DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).map_err(WindowsFontError::from)?;
+            if let Ok(factory6) = factory.cast::<IDWriteFactory6>() {
+                let typographic = factory6
+                    .CreateFontCollectionFromFontSet2(
+                        &fontset,
+                        DWRITE_FONT_FAMILY_MODEL_TYPOGRAPHIC,
+                    )
+                    .map_err(WindowsFontError::from)?;
+                if let Some(family) =
+                    Self::_find_family(&typographic, name).map_err(WindowsFontError::from)?
+                {
+                    push(family);
+                }
+            }
+
+            for family in self
+                ._families_with_property(DWRITE_FONT_PROPERTY_ID_WIN32_FAMILY_NAME, name)
+                .map_err(WindowsFontError::from)?
+            {
+                push(family);
+            }
+        }
+        Ok(families)
+    }
+}
+
+impl PartialEq for FontCollection {
+    fn eq(&self, other: &Self) -> bool {
+        (self.is_system && other.is_system) || self.collection == other.collection
     }
 }
 
 trait BestLocaleName {
     unsafe fn get_best_name(&self) -> Result<String>;
+    unsafe fn get_all_strings(&self) -> Result<Vec<String>>;
+    unsafe fn get_string_at(&self, index: u32) -> Result<String>;
 }
 
 impl BestLocaleName for IDWriteLocalizedStrings {
     unsafe fn get_best_name(&self) -> Result<String> {
+        if self.GetCount() == 0 {
+            // Some malformed fonts have no names at all. Don't try to index into an empty list
+            return Ok(String::new());
+        }
+
         let mut index = 0u32;
 
         USER_LOCALE.with(|locale| -> Result<()> {
             let mut found = BOOL(0);
-            let res = self.FindLocaleName(Into::<PCWSTR>::into(locale), &mut index, &mut found);
+            let locale = locale.borrow();
+            let res = self.FindLocaleName(Into::<PCWSTR>::into(&*locale), &mut index, &mut found);
 
             if res.is_ok() && !found.as_bool() {
                 // Fallback to en-us locale
@@ -236,13 +898,27 @@ impl BestLocaleName for IDWriteLocalizedStrings {
             Ok(())
         })?;
 
+        self.get_string_at(index)
+    }
+
+    /// Every string in the list, regardless of locale
+    unsafe fn get_all_strings(&self) -> Result<Vec<String>> {
+        (0..self.GetCount())
+            .map(|i| self.get_string_at(i))
+            .collect()
+    }
+
+    unsafe fn get_string_at(&self, index: u32) -> Result<String> {
         let len = self.GetStringLength(index)? as usize;
 
         let mut buff = Vec::new();
         buff.resize(len + 1, 0u16);
         self.GetString(index, buff.as_mut_slice())?;
 
-        Ok(String::from_utf16(slice::from_raw_parts(buff.as_ptr(), len)).unwrap())
+        Ok(String::from_utf16_lossy(slice::from_raw_parts(
+            buff.as_ptr(),
+            len,
+        )))
     }
 }
 
@@ -261,92 +937,520 @@ impl From<FloatOrWeight> for f32 {
     }
 }
 
-type ResultFontVariantIter = Box<dyn std::iter::Iterator<Item = Result<FontVariant>>>;
+/// A single weight, or an inclusive `(min, max)` range of weights
+#[derive(FromPyObject)]
+enum WeightQuery {
+    Value(FloatOrWeight),
+    Range((FloatOrWeight, FloatOrWeight)),
+}
 
-#[pyclass(sequence, module = "windows_fonts", unsendable)]
-#[derive(Clone, Debug)]
-struct FontFamily(IDWriteFontFamily);
+/// A single axis value, or an inclusive `(min, max)` range of values
+#[derive(FromPyObject)]
+enum AxisQuery {
+    Value(f32),
+    Range((f32, f32)),
+}
 
-impl FontFamily {
-    /// Get the name from the "best" available locale, or first as a fallback
-    unsafe fn _get_best_name(&self) -> Result<String> {
-        let names = self.0.GetFamilyNames()?;
-        names.get_best_name()
+impl From<WeightQuery> for AxisQuery {
+    fn from(q: WeightQuery) -> Self {
+        match q {
+            WeightQuery::Value(v) => AxisQuery::Value(v.into()),
+            WeightQuery::Range((min, max)) => AxisQuery::Range((min.into(), max.into())),
+        }
     }
+}
 
-    fn _get_matcing_variants(
-        rc: Py<Self>,
-        weight: Option<FloatOrWeight>,
-        style: Option<enums::Style>,
-        width: Option<f32>,
-        slant: Option<f32>,
-        optical_size: Option<f32>,
-        italic: Option<bool>,
-        py: Python<'_>,
-    ) -> anyhow::Result<ResultFontVariantIter> {
-        if style.is_some() {
-            // Windows 7 path
-            if width.is_some() || slant.is_some() || optical_size.is_some() || italic.is_some() {
-                bail!(PyValueError::new_err("cannot pass `style` and any of `width`, `slant`, `optical_size`, `italic` at the same time"));
-            }
-            unsafe {
-                FontFamily::_get_dwrite0_matching_variants(rc, weight.map(Into::into), style, py)
-            }
-        } else {
-            unsafe {
-                FontFamily::_get_dwrite3_matching_variants(
-                    rc,
-                    weight.map(Into::into),
-                    width,
-                    slant,
-                    optical_size,
-                    italic,
-                    py,
-                )
+impl AxisQuery {
+    /// The value to ask DirectWrite to rank by, and the range (if any) to filter the results to afterwards
+    fn split(self) -> (f32, Option<(f32, f32)>) {
+        match self {
+            AxisQuery::Value(v) => (v, None),
+            AxisQuery::Range((a, b)) => {
+                let (min, max) = if a <= b { (a, b) } else { (b, a) };
+                ((min + max) / 2.0, Some((min, max)))
             }
         }
     }
+}
 
-    // Windows 7 compatible!
-    unsafe fn _get_dwrite0_matching_variants(
-        rc: Py<Self>,
-        weight: Option<f32>,
-        style: Option<enums::Style>,
-        py: Python<'_>,
-    ) -> anyhow::Result<ResultFontVariantIter> {
-        let copy = rc.clone();
-        let self_ = copy.borrow(py);
-        let list = match self_.0.GetMatchingFonts(
-            DWRITE_FONT_WEIGHT(weight.unwrap_or(400.0) as i32),
-            DWRITE_FONT_STRETCH_NORMAL,
-            DWRITE_FONT_STYLE(style.unwrap_or(enums::Style::NORMAL) as i32),
-        ) {
-            Ok(l) => l,
-            Err(e) => {
-                bail!(e)
-            }
-        };
-
-        let num = list.GetFontCount();
-        let iter = (0..num).map(move |n| -> Result<FontVariant> {
-            let font = list.GetFont(n).map_err(WindowsFontError::from)?;
+/// Convert a dict of custom variation axis tags (such as ``"GRAD"``) to values into axis values for DirectWrite
+fn custom_axis_values(
+    custom_axes: Option<HashMap<String, f32>>,
+) -> PyResult<Vec<DWRITE_FONT_AXIS_VALUE>> {
+    let mut values = custom_axes
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(tag, value)| {
+            let tag = tables::tag_from_str(&tag).ok_or_else(|| {
+                PyValueError::new_err(format!("{tag:?} is not a four character axis tag"))
+            })?;
+            Ok(DWRITE_FONT_AXIS_VALUE {
+                axisTag: DWRITE_FONT_AXIS_TAG(tag),
+                value,
+            })
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    // Dict order shouldn't change which fonts match, so hand them to DirectWrite in a stable order
+    values.sort_by_key(|v| v.axisTag.0);
+    Ok(values)
+}
 
-            Ok(FontVariant {
-                font: Rc::new(font),
-                family: rc.clone(),
+/// Convert a dict of OpenType feature tags (such as ``"smcp"``) to values into features for DirectWrite shaping
+fn font_features(features: Option<HashMap<String, u32>>) -> PyResult<Vec<DWRITE_FONT_FEATURE>> {
+    let mut features = features
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(tag, parameter)| {
+            let tag = tables::tag_from_str(&tag).ok_or_else(|| {
+                PyValueError::new_err(format!("{tag:?} is not a four character feature tag"))
+            })?;
+            Ok(DWRITE_FONT_FEATURE {
+                nameTag: DWRITE_FONT_FEATURE_TAG(tag),
+                parameter,
             })
-        });
-        Ok(Box::new(iter))
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+    features.sort_by_key(|f| f.nameTag.0);
+    Ok(features)
+}
+
+// The usWidthClass to percentage mapping from the OpenType OS/2 table spec, in order of stretch value
+const STRETCH_WIDTHS: [f32; 9] = [50.0, 62.5, 75.0, 87.5, 100.0, 112.5, 125.0, 150.0, 200.0];
+
+/// Convert a width axis value (a percentage of normal width) to the nearest `DWRITE_FONT_STRETCH` class
+fn width_to_stretch(width: f32) -> DWRITE_FONT_STRETCH {
+    let mut best = 0;
+    for (i, w) in STRETCH_WIDTHS.iter().enumerate() {
+        if (w - width).abs() < (STRETCH_WIDTHS[best] - width).abs() {
+            best = i;
+        }
     }
+    DWRITE_FONT_STRETCH(best as i32 + 1)
+}
 
-    // Windows 10 Build 20348
-    unsafe fn _get_dwrite3_matching_variants(
-        rc: Py<Self>,
-        weight: Option<f32>,
+/// Whether a font file path is in the per-user (``"user"``) or machine-wide (``"machine"``) fonts folder, or `None`
+/// if it's in neither
+fn install_scope_for_path(path: &str) -> Option<&'static str> {
+    let path = path.to_lowercase();
+    let in_dir = |var: &str, sub: &str| {
+        std::env::var(var).is_ok_and(|base| {
+            let dir = format!("{}\\{}\\", base.trim_end_matches('\\'), sub);
+            path.starts_with(&dir.to_lowercase())
+        })
+    };
+    if in_dir("LOCALAPPDATA", r"Microsoft\Windows\Fonts") {
+        Some("user")
+    } else if in_dir("WINDIR", "Fonts") {
+        Some("machine")
+    } else {
+        None
+    }
+}
+
+/// The Levenshtein distance between `a` and `b`, ignoring case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    // Only the previous row of the table is needed
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            row[j + 1] = substitution.min(prev[j + 1] + 1).min(row[j] + 1);
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// The CSS ``font-style`` keyword for a `DWRITE_FONT_STYLE`
+fn css_style_name(style: DWRITE_FONT_STYLE) -> &'static str {
+    match style {
+        DWRITE_FONT_STYLE_ITALIC => "italic",
+        DWRITE_FONT_STYLE_OBLIQUE => "oblique",
+        _ => "normal",
+    }
+}
+
+/// The CSS ``font-stretch`` keyword for a `DWRITE_FONT_STRETCH`
+fn css_stretch_name(stretch: DWRITE_FONT_STRETCH) -> &'static str {
+    // In the same order as the DWRITE_FONT_STRETCH values
+    const STRETCHES: [&str; 9] = [
+        "ultra-condensed",
+        "extra-condensed",
+        "condensed",
+        "semi-condensed",
+        "normal",
+        "semi-expanded",
+        "expanded",
+        "extra-expanded",
+        "ultra-expanded",
+    ];
+    (stretch.0 as usize)
+        .checked_sub(1)
+        .and_then(|i| STRETCHES.get(i))
+        .copied()
+        .unwrap_or("normal")
+}
+
+/// The display name of the standard weight nearest to `weight`, as used in style names such as "Semi Bold Italic"
+///
+/// Ties go to the lighter weight, like `Weight::nearest`.
+fn weight_display_name(weight: i32) -> &'static str {
+    const WEIGHTS: [(i32, &str); 11] = [
+        (100, "Thin"),
+        (200, "Extra Light"),
+        (300, "Light"),
+        (350, "Semi Light"),
+        (400, "Regular"),
+        (500, "Medium"),
+        (600, "Semi Bold"),
+        (700, "Bold"),
+        (800, "Extra Bold"),
+        (900, "Black"),
+        (950, "Extra Black"),
+    ];
+    WEIGHTS
+        .iter()
+        .min_by_key(|(value, _)| (value - weight).abs())
+        .map_or("Regular", |(_, name)| name)
+}
+
+/// The display name of a `DWRITE_FONT_STRETCH`, as used in style names such as "Semi Condensed Bold"
+fn stretch_display_name(stretch: DWRITE_FONT_STRETCH) -> &'static str {
+    // In the same order as the DWRITE_FONT_STRETCH values
+    const STRETCHES: [&str; 9] = [
+        "Ultra Condensed",
+        "Extra Condensed",
+        "Condensed",
+        "Semi Condensed",
+        "Normal",
+        "Semi Expanded",
+        "Expanded",
+        "Extra Expanded",
+        "Ultra Expanded",
+    ];
+    (stretch.0 as usize)
+        .checked_sub(1)
+        .and_then(|i| STRETCHES.get(i))
+        .copied()
+        .unwrap_or("Normal")
+}
+
+/// Convert a `DWRITE_FONT_STRETCH` class to its width axis value (a percentage of normal width)
+fn stretch_to_width(stretch: DWRITE_FONT_STRETCH) -> Option<f32> {
+    STRETCH_WIDTHS
+        .get((stretch.0 as usize).checked_sub(1)?)
+        .copied()
+}
+
+type ResultFontVariantIter = Box<dyn std::iter::Iterator<Item = Result<FontVariant>>>;
+
+/// The (loader, reference key) of each file of a face, its index and its simulations
+type FaceIdentity = (Vec<(usize, Vec<u8>)>, u32, u32);
+
+/// A layer of a color glyph: its glyph index, and its color as RGBA (`None` for the text color)
+type ColorLayer = (u16, Option<(u8, u8, u8, u8)>);
+
+#[pyclass(module = "windows_fonts", unsendable)]
+struct FontVariantIter {
+    iter: ResultFontVariantIter,
+}
+
+#[pymethods]
+impl FontVariantIter {
+    fn __next__(&mut self) -> Result<Option<FontVariant>> {
+        self.iter.next().transpose()
+    }
+
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+}
+
+#[pyclass(module = "windows_fonts", unsendable)]
+struct FamilyNameIter {
+    collection: IDWriteFontCollection1,
+    next: u32,
+}
+
+#[pymethods]
+impl FamilyNameIter {
+    fn __next__(&mut self) -> Result<Option<(String, FontFamily)>> {
+        if self.next >= unsafe { self.collection.GetFontFamilyCount() } {
+            return Ok(None);
+        }
+        let family = FontFamily::new(unsafe { self.collection.GetFontFamily(self.next)? });
+        self.next += 1;
+        let name = unsafe { family._get_best_name()? };
+        Ok(Some((name, family)))
+    }
+
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+}
+
+#[pyclass(module = "windows_fonts", unsendable)]
+struct ReversedFamilyIter {
+    collection: IDWriteFontCollection1,
+    remaining: u32,
+}
+
+#[pymethods]
+impl ReversedFamilyIter {
+    fn __next__(&mut self) -> Result<Option<FontFamily>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        self.remaining -= 1;
+        let family = unsafe { self.collection.GetFontFamily(self.remaining)? };
+        Ok(Some(FontFamily::new(family)))
+    }
+
+    pub fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+}
+
+#[pyclass(sequence, module = "windows_fonts", unsendable)]
+#[derive(Clone, Debug)]
+// The best name is looked up the first time it is needed and then cached, as comparisons need it over and over
+struct FontFamily(IDWriteFontFamily, OnceCell<String>);
+
+impl FontFamily {
+    fn new(family: IDWriteFontFamily) -> Self {
+        FontFamily(family, OnceCell::new())
+    }
+
+    /// Get the name from the "best" available locale, or first as a fallback
+    unsafe fn _get_best_name(&self) -> Result<String> {
+        if let Some(name) = self.1.get() {
+            return Ok(name.clone());
+        }
+        let name = self.0.GetFamilyNames()?.get_best_name()?;
+        Ok(self.1.get_or_init(|| name).clone())
+    }
+
+    fn _get_matcing_variants(
+        rc: Py<Self>,
+        weight: Option<FloatOrWeight>,
+        style: Option<enums::Style>,
+        width: Option<f32>,
+        slant: Option<f32>,
+        optical_size: Option<f32>,
+        italic: Option<bool>,
+        custom_axes: &[DWRITE_FONT_AXIS_VALUE],
+        py: Python<'_>,
+    ) -> anyhow::Result<ResultFontVariantIter> {
+        if style.is_some() {
+            // Windows 7 path
+            if slant.is_some()
+                || optical_size.is_some()
+                || italic.is_some()
+                || !custom_axes.is_empty()
+            {
+                bail!(PyValueError::new_err("cannot pass `style` and any of `slant`, `optical_size`, `italic`, `custom_axes` at the same time"));
+            }
+            unsafe {
+                FontFamily::_get_dwrite0_matching_variants(
+                    rc,
+                    weight.map(Into::into),
+                    width.map(width_to_stretch),
+                    style,
+                    py,
+                )
+            }
+        } else {
+            unsafe {
+                FontFamily::_get_dwrite3_matching_variants(
+                    rc,
+                    weight.map(Into::into),
+                    width,
+                    slant,
+                    optical_size,
+                    italic,
+                    custom_axes,
+                    py,
+                )
+            }
+        }
+    }
+
+    /// An informational string of the family's most regular variant, or `None` if it doesn't have it or the family is
+    /// empty
+    unsafe fn _get_info_string(
+        &self,
+        id: DWRITE_INFORMATIONAL_STRING_ID,
+    ) -> Result<Option<String>> {
+        if self.0.GetFontCount() == 0 {
+            return Ok(None);
+        }
+        let font = self.0.GetFirstMatchingFont(
+            DWRITE_FONT_WEIGHT_NORMAL,
+            DWRITE_FONT_STRETCH_NORMAL,
+            DWRITE_FONT_STYLE_NORMAL,
+        )?;
+        match Rc::new(font).get_info_string(id)? {
+            Some(strings) => Ok(Some(strings.get_best_name()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The index of the variant with the face name `name`, ignoring case
+    unsafe fn _find_face_name(&self, name: &str) -> Result<Option<u32>> {
+        let wanted = name.to_lowercase();
+        for i in 0..self.0.GetFontCount() {
+            let face_name = self.0.GetFont(i)?.GetFaceNames()?.get_best_name()?;
+            if face_name.to_lowercase() == wanted {
+                return Ok(Some(i));
+            }
+        }
+        Ok(None)
+    }
+
+    fn _get_first_matching_variant(
+        rc: Py<Self>,
+        weight: enums::Weight,
+        style: enums::Style,
+        py: Python<'_>,
+    ) -> Result<Option<FontVariant>> {
+        let mut iter = FontFamily::_get_matcing_variants(
+            rc,
+            Some(FloatOrWeight::Enum(weight)),
+            Some(style),
+            None,
+            None,
+            None,
+            None,
+            &[],
+            py,
+        )?;
+        iter.next().transpose()
+    }
+
+    // Windows 7 compatible!
+    unsafe fn _get_dwrite0_matching_variants(
+        rc: Py<Self>,
+        weight: Option<f32>,
+        stretch: Option<DWRITE_FONT_STRETCH>,
+        style: Option<enums::Style>,
+        py: Python<'_>,
+    ) -> anyhow::Result<ResultFontVariantIter> {
+        let copy = rc.clone();
+        let self_ = copy.borrow(py);
+        let list = match self_.0.GetMatchingFonts(
+            DWRITE_FONT_WEIGHT(weight.unwrap_or(400.0) as i32),
+            stretch.unwrap_or(DWRITE_FONT_STRETCH_NORMAL),
+            DWRITE_FONT_STYLE(style.unwrap_or(enums::Style::NORMAL) as i32),
+        ) {
+            Ok(l) => l,
+            Err(e) => {
+                bail!(e)
+            }
+        };
+
+        let num = list.GetFontCount();
+        let iter = (0..num).map(move |n| -> Result<FontVariant> {
+            let font = list.GetFont(n).map_err(WindowsFontError::from)?;
+
+            Ok(FontVariant::new(Rc::new(font), rc.clone()))
+        });
+        Ok(Box::new(iter))
+    }
+
+    // The oldest API for this, available in every version of DirectWrite. Used as a fallback when the font list can't
+    // be retrieved
+    unsafe fn _get_dwrite0_first_matching_variant(
+        rc: Py<Self>,
+        weight: Option<f32>,
+        stretch: Option<DWRITE_FONT_STRETCH>,
+        style: Option<enums::Style>,
+        py: Python<'_>,
+    ) -> Result<FontVariant> {
+        let font = Self::_dwrite0_first_matching_font(&rc.borrow(py).0, weight, stretch, style)
+            .map_err(WindowsFontError::from)?;
+
+        Ok(FontVariant::new(Rc::new(font), rc))
+    }
+
+    /// `GetFirstMatchingFont`, with the same defaults as `_get_dwrite0_matching_variants` uses for `GetMatchingFonts`
+    unsafe fn _dwrite0_first_matching_font(
+        family: &IDWriteFontFamily,
+        weight: Option<f32>,
+        stretch: Option<DWRITE_FONT_STRETCH>,
+        style: Option<enums::Style>,
+    ) -> windows::core::Result<IDWriteFont> {
+        family.GetFirstMatchingFont(
+            DWRITE_FONT_WEIGHT(weight.unwrap_or(400.0) as i32),
+            stretch.unwrap_or(DWRITE_FONT_STRETCH_NORMAL),
+            DWRITE_FONT_STYLE(style.unwrap_or(enums::Style::NORMAL) as i32),
+        )
+    }
+
+    /// The optical size to match with: an explicit `optical_size` always wins, otherwise it is derived from `at_size`
+    /// when the family has an optical size axis
+    fn _optical_size_for(
+        rc: &Py<Self>,
+        optical_size: Option<f32>,
+        at_size: Option<f32>,
+        style: &Option<enums::Style>,
+        py: Python<'_>,
+    ) -> Option<f32> {
+        if optical_size.is_some() {
+            return optical_size;
+        }
+        let size_px = at_size?;
+        if style.is_some() || !rc.borrow(py)._has_axis(DWRITE_FONT_AXIS_TAG_OPTICAL_SIZE) {
+            return None;
+        }
+        // The opsz axis is in points, and there are 72 points to 96 pixels
+        Some(size_px * 72.0 / 96.0)
+    }
+
+    /// The sorted, distinct values of `f` across every font in the family
+    unsafe fn _distinct_font_values(&self, f: impl Fn(&IDWriteFont) -> i32) -> Result<Vec<i32>> {
+        let mut values = Vec::new();
+        for i in 0..self.0.GetFontCount() {
+            values.push(f(&self.0.GetFont(i)?));
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(values)
+    }
+
+    /// Whether any font in the family is variable along the given axis. Always `false` before Windows 10 Build 17134
+    fn _has_axis(&self, tag: DWRITE_FONT_AXIS_TAG) -> bool {
+        unsafe {
+            (0..self.0.GetFontCount()).any(|i| {
+                let resource = self
+                    .0
+                    .GetFont(i)
+                    .and_then(|font| font.CreateFontFace())
+                    .and_then(|face| face.cast::<IDWriteFontFace5>())
+                    .and_then(|face5| face5.GetFontResource());
+                let resource = match resource {
+                    Ok(r) if r.HasVariations().as_bool() => r,
+                    _ => return false,
+                };
+                let mut defaults =
+                    vec![DWRITE_FONT_AXIS_VALUE::default(); resource.GetFontAxisCount() as usize];
+                resource.GetDefaultFontAxisValues(&mut defaults).is_ok()
+                    && defaults.iter().any(|v| v.axisTag == tag)
+            })
+        }
+    }
+
+    // Windows 10 Build 20348
+    unsafe fn _get_dwrite3_matching_variants(
+        rc: Py<Self>,
+        weight: Option<f32>,
         width: Option<f32>,
         slant: Option<f32>,
         optical_size: Option<f32>,
         italic: Option<bool>,
+        custom_axes: &[DWRITE_FONT_AXIS_VALUE],
         py: Python<'_>,
     ) -> anyhow::Result<ResultFontVariantIter> {
         // ) -> impl Iterator<Item = anyhow::Result<FontVariant>> {
@@ -396,6 +1500,8 @@ impl FontFamily {
             });
         }
 
+        conditions.extend_from_slice(custom_axes);
+
         let list = match family.GetMatchingFonts2(&conditions) {
             Ok(l) => l,
             Err(e) => {
@@ -407,10 +1513,7 @@ impl FontFamily {
         let iter = (0..num).map(move |n| -> Result<FontVariant> {
             let font = list.GetFont(n).map_err(WindowsFontError::from)?;
 
-            Ok(FontVariant {
-                font: Rc::new(font),
-                family: rc.clone(),
-            })
+            Ok(FontVariant::new(Rc::new(font), rc.clone()))
         });
 
         Ok(Box::new(iter))
@@ -424,6 +1527,21 @@ impl FontFamily {
         unsafe { self._get_best_name() }
     }
 
+    /// The typographic (preferred) family name (name ID 16) of the family's fonts, or `None` if they don't have one
+    ///
+    /// Under the weight-stretch-style model :attr:`name` can be a narrower family, such as ``"Arial Narrow"``, whose
+    /// fonts have a typographic family of ``"Arial"``. This is read from the family's most regular variant.
+    #[getter]
+    pub fn typographic_family_name(&self) -> Result<Option<String>> {
+        unsafe { self._get_info_string(DWRITE_INFORMATIONAL_STRING_TYPOGRAPHIC_FAMILY_NAMES) }
+    }
+
+    /// The same as :attr:`typographic_family_name`, under the name older versions of OpenType used for it
+    #[getter]
+    pub fn preferred_family_name(&self) -> Result<Option<String>> {
+        unsafe { self._get_info_string(DWRITE_INFORMATIONAL_STRING_PREFERRED_FAMILY_NAMES) }
+    }
+
     pub fn __repr__(&self) -> Result<String> {
         Ok(format!("<FontFamily name={:?}>", self.name()?,))
     }
@@ -432,21 +1550,120 @@ impl FontFamily {
         unsafe { self.0.GetFontCount() as usize }
     }
 
-    pub fn __getitem__(rc: Py<Self>, mut index: i32, py: Python<'_>) -> PyResult<FontVariant> {
+    /// The variant that can display the most characters of `text`, preferring the most regular looking face (normal
+    /// weight, width and style, and not simulated) when several cover it equally well
+    ///
+    /// Returns `None` if the family is empty.
+    #[pyo3(text_signature = "($self, text)")]
+    pub fn best_variant_for_text(
+        rc: Py<Self>,
+        text: &str,
+        py: Python<'_>,
+    ) -> Result<Option<FontVariant>> {
+        let mut best: Option<((usize, std::cmp::Reverse<i32>), IDWriteFont)> = None;
+        unsafe {
+            let family = &rc.borrow(py).0;
+            for i in 0..family.GetFontCount() {
+                let font = family.GetFont(i)?;
+                let covered = text
+                    .chars()
+                    .filter(|&c| matches!(font.HasCharacter(c as u32), Ok(b) if b.as_bool()))
+                    .count();
+                // How far this face is from a plain regular face, lower is better
+                let distance = (font.GetWeight().0 - DWRITE_FONT_WEIGHT_REGULAR.0).abs()
+                    + (font.GetStretch().0 - DWRITE_FONT_STRETCH_NORMAL.0).abs() * 100
+                    + if font.GetStyle() == DWRITE_FONT_STYLE_NORMAL {
+                        0
+                    } else {
+                        1000
+                    }
+                    + if font.GetSimulations() == DWRITE_FONT_SIMULATIONS_NONE {
+                        0
+                    } else {
+                        1000
+                    };
+                let score = (covered, std::cmp::Reverse(distance));
+                let better = match &best {
+                    Some((best_score, _)) => score > *best_score,
+                    None => true,
+                };
+                if better {
+                    best = Some((score, font));
+                }
+            }
+        }
+        Ok(best.map(|(_, font)| FontVariant::new(Rc::new(font), rc.clone())))
+    }
+
+    /// The distinct font files backing the variants of this family, as a list of ``(path, [face_index, ...])``
+    ///
+    /// Several variants can share a single collection (``.ttc``) file, so this lets callers load each file just once.
+    /// Files are listed in the order they are first used by the family's variants. Variants loaded from memory have no
+    /// file, so aren't included.
+    #[pyo3(text_signature = "($self)")]
+    pub fn files(&self) -> Result<Vec<(String, Vec<u32>)>> {
+        let mut files: Vec<(String, Vec<u32>)> = Vec::new();
+        unsafe {
+            for i in 0..self.0.GetFontCount() {
+                let face = self.0.GetFont(i)?.CreateFontFace()?;
+                let index = face.GetIndex();
+                for path in FontVariant::_get_face_file_paths(&face)? {
+                    match files.iter_mut().find(|(p, _)| *p == path) {
+                        Some((_, indices)) => {
+                            if !indices.contains(&index) {
+                                indices.push(index)
+                            }
+                        }
+                        None => files.push((path, vec![index])),
+                    }
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    /// The distinct weights of the variants in this family, as sorted numeric values (such as ``[300, 400, 700]``)
+    #[pyo3(text_signature = "($self)")]
+    pub fn available_weights(&self) -> Result<Vec<i32>> {
+        unsafe { self._distinct_font_values(|font| font.GetWeight().0) }
+    }
+
+    /// The distinct stretches of the variants in this family, as sorted numeric ``DWRITE_FONT_STRETCH`` values from
+    /// 1 (ultra-condensed) to 9 (ultra-expanded)
+    #[pyo3(text_signature = "($self)")]
+    pub fn available_stretches(&self) -> Result<Vec<i32>> {
+        unsafe { self._distinct_font_values(|font| font.GetStretch().0) }
+    }
+
+    /// Whether this family has a variant with the given face name (such as ``"Bold Italic"``), ignoring case
+    pub fn __contains__(&self, name: &PyAny) -> Result<bool> {
+        match name.downcast::<PyString>() {
+            Ok(s) => Ok(unsafe { self._find_face_name(s.to_str()?) }?.is_some()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Get a variant by its index, or by its face name (such as ``"Bold Italic"``), ignoring case
+    pub fn __getitem__(rc: Py<Self>, key: IntOrStr, py: Python<'_>) -> PyResult<FontVariant> {
         unsafe {
             let self_ = rc.borrow(py);
-            if index < 0 {
-                index += self_.0.GetFontCount() as i32;
-            }
-            match self_.0.GetFont(index as u32) {
-                Ok(font) => Ok(FontVariant {
-                    font: Rc::new(font),
-                    family: rc.clone(),
-                }),
-                Err(_) => Err(PyIndexError::new_err(format!(
-                    "key {:?} out of range",
-                    index
-                ))),
+            let index = match key {
+                IntOrStr::Str(name) => {
+                    let name = name.to_str()?;
+                    match self_._find_face_name(name)? {
+                        Some(index) => index as isize,
+                        None => return Err(PyKeyError::new_err(name.to_owned())),
+                    }
+                }
+                IntOrStr::Int(index) if index < 0 => index + self_.0.GetFontCount() as isize,
+                IntOrStr::Int(index) => index,
+            };
+            let out_of_range = || PyIndexError::new_err(format!("key {:?} out of range", index));
+            // Anything that doesn't fit is out of range, rather than wrapping around to a valid index
+            let font_index = u32::try_from(index).map_err(|_| out_of_range())?;
+            match self_.0.GetFont(font_index) {
+                Ok(font) => Ok(FontVariant::new(Rc::new(font), rc.clone())),
+                Err(_) => Err(out_of_range()),
             }
         }
     }
@@ -459,13 +1676,51 @@ impl FontFamily {
         }
     }
 
+    /// The variant that best matches a regular weight, normal style face, or `None` if the family is empty
+    #[getter]
+    fn regular(rc: Py<Self>, py: Python<'_>) -> Result<Option<FontVariant>> {
+        FontFamily::_get_first_matching_variant(
+            rc,
+            enums::Weight::REGULAR,
+            enums::Style::NORMAL,
+            py,
+        )
+    }
+
+    /// The variant that best matches a bold weight, normal style face, or `None` if the family is empty
+    #[getter]
+    fn bold(rc: Py<Self>, py: Python<'_>) -> Result<Option<FontVariant>> {
+        FontFamily::_get_first_matching_variant(rc, enums::Weight::BOLD, enums::Style::NORMAL, py)
+    }
+
+    /// The variant that best matches a regular weight, italic style face, or `None` if the family is empty
+    #[getter]
+    fn italic(rc: Py<Self>, py: Python<'_>) -> Result<Option<FontVariant>> {
+        FontFamily::_get_first_matching_variant(
+            rc,
+            enums::Weight::REGULAR,
+            enums::Style::ITALIIC,
+            py,
+        )
+    }
+
+    /// The variant that best matches a bold weight, italic style face, or `None` if the family is empty
+    #[getter]
+    fn bold_italic(rc: Py<Self>, py: Python<'_>) -> Result<Option<FontVariant>> {
+        FontFamily::_get_first_matching_variant(rc, enums::Weight::BOLD, enums::Style::ITALIIC, py)
+    }
+
     /// Retrieves the best matching variant for the various conditions
     ///
     /// Returns the first variant from :meth:`get_matching_variants` (but more efficiently, without creating
-    /// extra objects)
+    /// extra objects). See there for the meaning of `at_size` and `custom_axes`.
+    ///
+    /// When the family has no face close to what was asked for, the result may be synthesized by DirectWrite from
+    /// another face; :attr:`FontVariant.simulations` says how, so the simulation can be applied when rendering.
     #[pyo3(
-        text_signature = "($self, *, weight=None, style=None, width=None, slant=None, optical_size=None, italic=None)"
+        text_signature = "($self, *, weight=None, style=None, width=None, slant=None, optical_size=None, italic=None, at_size=None, custom_axes=None)"
     )]
+    #[allow(clippy::too_many_arguments)]
     fn get_best_variant(
         rc: Py<Self>,
         weight: Option<FloatOrWeight>,
@@ -474,18 +1729,48 @@ impl FontFamily {
         slant: Option<f32>,
         optical_size: Option<f32>,
         italic: Option<bool>,
+        at_size: Option<f32>,
+        custom_axes: Option<HashMap<String, f32>>,
         py: Python<'_>,
     ) -> Result<FontVariant> {
-        let mut iter = FontFamily::_get_matcing_variants(
-            rc,
-            weight,
-            style,
+        let custom_axes = custom_axis_values(custom_axes)?;
+        let weight: Option<f32> = weight.map(Into::into);
+        let optical_size = FontFamily::_optical_size_for(&rc, optical_size, at_size, &style, py);
+        let matching = FontFamily::_get_matcing_variants(
+            rc.clone(),
+            weight.map(FloatOrWeight::Float),
+            style.clone(),
             width,
             slant,
             optical_size,
             italic,
+            &custom_axes,
             py,
-        )?;
+        );
+
+        let mut iter = match matching {
+            Ok(iter) => iter,
+            // GetFirstMatchingFont only knows about weight, stretch and style, so only fall back to it when those are
+            // the only conditions we were asked for
+            Err(e)
+                if slant.is_none()
+                    && optical_size.is_none()
+                    && italic.is_none()
+                    && custom_axes.is_empty() =>
+            {
+                return unsafe {
+                    FontFamily::_get_dwrite0_first_matching_variant(
+                        rc,
+                        weight,
+                        width.map(width_to_stretch),
+                        style,
+                        py,
+                    )
+                }
+                .map_err(|_| e);
+            }
+            Err(e) => return Err(e),
+        };
 
         if let Some(item) = iter.next() {
             return item;
@@ -495,8 +1780,9 @@ impl FontFamily {
 
     /// Retrieves a list of fonts in the font family, ranked in order of how well they match the specified axis values.
     ///
-    /// On Windows 10 and below, only weight and style are allowed. It is not allowed to pass any of width,
-    /// sland, optical_size and italic at the same time as style.
+    /// On Windows 10 and below, only weight, width and style are allowed. It is not allowed to pass any of slant,
+    /// optical_size and italic at the same time as style. When `style` is given, `width` is mapped to the nearest
+    /// `DWRITE_FONT_STRETCH` value.
     ///
     /// For weight, and style see https://learn.microsoft.com/en-us/windows/win32/api/dwrite/nf-dwrite-idwritefontfamily-getmatchingfonts
     ///
@@ -504,27 +1790,74 @@ impl FontFamily {
     /// https://learn.microsoft.com/en-us/windows/win32/api/dwrite_3/nf-dwrite_3-idwritefontfamily2-getmatchingfonts
     /// and https://learn.microsoft.com/en-us/windows/win32/api/dwrite_3/ns-dwrite_3-dwrite_font_axis_value
     /// for possible values
-    #[pyo3(
-        text_signature = "($self, *, weight=None, style=None, width=None, slant=None, optical_size=None, italic=None)"
-    )]
-    fn get_matching_variants(
+    ///
+    /// DirectWrite can include faces that are synthesized from another face (a faux bold or oblique), which report the
+    /// simulation they need in :attr:`FontVariant.simulations`. Pass `exclude_simulated=True` to only get back the
+    /// faces that were actually designed.
+    ///
+    /// `weight`, `width`, `slant` and `optical_size` can also be given as an inclusive ``(min, max)`` tuple. DirectWrite
+    /// only ranks fonts rather than filtering them, so the variants are ranked by the middle of the range and then any
+    /// whose value for that axis falls outside the range (or is unknown) are removed from the list.
+    ///
+    /// `at_size` is the size in pixels (at 96 DPI) the font will be rendered at. If the family has an optical size
+    /// (``opsz``) axis and `optical_size` isn't given, the optical size is set to match, converted to points as the
+    /// axis expects. It is ignored when `style` is passed, as optical size can't be combined with style.
+    ///
+    /// `custom_axes` is a dict of other variation axes to match on, from their four character tag (such as ``"GRAD"``)
+    /// to the wanted value. Like `slant`, it can't be combined with `style`.
+    ///
+    /// `limit` stops the list at that many variants (after any are removed by the options above), without creating
+    /// the rest, for when only the best few matches are wanted.
+    #[pyo3(
+        text_signature = "($self, *, weight=None, style=None, width=None, slant=None, optical_size=None, italic=None, at_size=None, custom_axes=None, exclude_simulated=False, limit=None)"
+    )]
+    #[args(exclude_simulated = "false", limit = "None")]
+    #[allow(clippy::too_many_arguments)]
+    fn get_matching_variants(
         rc: Py<Self>,
-        weight: Option<FloatOrWeight>,
+        weight: Option<WeightQuery>,
         style: Option<enums::Style>,
-        width: Option<f32>,
-        slant: Option<f32>,
-        optical_size: Option<f32>,
+        width: Option<AxisQuery>,
+        slant: Option<AxisQuery>,
+        optical_size: Option<AxisQuery>,
         italic: Option<bool>,
+        at_size: Option<f32>,
+        custom_axes: Option<HashMap<String, f32>>,
+        exclude_simulated: bool,
+        limit: Option<usize>,
         py: Python<'_>,
     ) -> Result<&'_ PyList> {
+        let custom_axes = custom_axis_values(custom_axes)?;
+        let optical_size = match optical_size {
+            Some(q) => Some(q),
+            None => {
+                FontFamily::_optical_size_for(&rc, None, at_size, &style, py).map(AxisQuery::Value)
+            }
+        };
+        let weight = weight.map(|q| AxisQuery::from(q).split());
+        let width = width.map(AxisQuery::split);
+        let slant = slant.map(AxisQuery::split);
+        let optical_size = optical_size.map(AxisQuery::split);
+
+        let ranges: Vec<(DWRITE_FONT_AXIS_TAG, (f32, f32))> = [
+            (DWRITE_FONT_AXIS_TAG_WEIGHT, weight),
+            (DWRITE_FONT_AXIS_TAG_WIDTH, width),
+            (DWRITE_FONT_AXIS_TAG_SLANT, slant),
+            (DWRITE_FONT_AXIS_TAG_OPTICAL_SIZE, optical_size),
+        ]
+        .iter()
+        .filter_map(|(tag, query)| Some((*tag, (*query)?.1?)))
+        .collect();
+
         let iter = FontFamily::_get_matcing_variants(
             rc,
-            weight,
+            weight.map(|(v, _)| FloatOrWeight::Float(v)),
             style,
-            width,
-            slant,
-            optical_size,
+            width.map(|(v, _)| v),
+            slant.map(|(v, _)| v),
+            optical_size.map(|(v, _)| v),
             italic,
+            &custom_axes,
             py,
         )?;
 
@@ -535,7 +1868,22 @@ impl FontFamily {
         };
 
         for item in iter {
-            variants.push(item?.into_py(py))
+            if limit.is_some_and(|limit| variants.len() >= limit) {
+                break;
+            }
+            let variant = item?;
+            if exclude_simulated
+                && unsafe { variant.font.GetSimulations() } != DWRITE_FONT_SIMULATIONS_NONE
+            {
+                continue;
+            }
+            let in_ranges = ranges.iter().all(|(tag, (min, max))| {
+                matches!(variant._axis_value(*tag), Some(v) if *min <= v && v <= *max)
+            });
+            if !in_ranges {
+                continue;
+            }
+            variants.push(variant.into_py(py))
         }
         Ok(PyList::new(py, variants))
     }
@@ -558,72 +1906,1610 @@ impl PartialEq for FontFamily {
                 return false;
             }
         }
-        name == other_name
+        name == other_name
+    }
+}
+
+#[pyclass(module = "windows_fonts", unsendable)]
+struct FontVariant {
+    font: Rc<IDWriteFont>,
+    // Keep the family alive so we can use it in `repr`, but don't create a _rust_ memory cycle
+    #[pyo3(get)]
+    family: Py<FontFamily>,
+    // Looked up the first time it is needed
+    name: OnceCell<String>,
+    family_key: OnceCell<(usize, u32)>,
+}
+
+impl FontVariant {
+    fn new(font: Rc<IDWriteFont>, family: Py<FontFamily>) -> Self {
+        FontVariant {
+            font,
+            family,
+            name: OnceCell::new(),
+            family_key: OnceCell::new(),
+        }
+    }
+}
+
+#[pymethods]
+impl FontVariant {
+    #[getter]
+    pub fn style(&self) -> enums::Style {
+        unsafe { self.font.GetStyle() }.into()
+    }
+
+    /// The weight of this variant, or the nearest named :class:`Weight` for weights such as 450 that aren't one of the
+    /// named values (see :attr:`is_standard_weight`)
+    #[getter]
+    pub fn weight(&self) -> enums::Weight {
+        let value = unsafe { self.font.GetWeight() }.0;
+        enums::Weight::from_value(value).unwrap_or_else(|| enums::Weight::nearest(value))
+    }
+
+    /// The named :class:`Weight` closest to the weight of this variant, for fonts with weights such as 450 that aren't
+    /// one of the named values. Ties go to the lighter weight
+    #[getter]
+    pub fn nearest_named_weight(&self) -> enums::Weight {
+        enums::Weight::nearest(unsafe { self.font.GetWeight() }.0)
+    }
+
+    /// The standard display name of this variant's weight, such as ``"Semi Bold"``, from its numeric weight rather than
+    /// its own names
+    ///
+    /// Weights between the standard ones get the name of the nearest, with ties going to the lighter weight. Along
+    /// with :attr:`stretch_name` this gives consistent style labels even for fonts whose own names are inconsistent.
+    #[getter]
+    pub fn weight_name(&self) -> &'static str {
+        weight_display_name(unsafe { self.font.GetWeight() }.0)
+    }
+
+    /// Whether the weight of this variant is exactly one of the named :class:`Weight` values
+    #[getter]
+    pub fn is_standard_weight(&self) -> bool {
+        enums::Weight::from_value(unsafe { self.font.GetWeight() }.0).is_some()
+    }
+
+    /// Whether this variant is bold or heavier, with a weight of at least 700
+    #[getter]
+    pub fn is_bold(&self) -> bool {
+        unsafe { self.font.GetWeight() }.0 >= DWRITE_FONT_WEIGHT_BOLD.0
+    }
+
+    /// Whether this is the plain variant of its family: a weight of exactly 400, and upright (not italic or oblique)
+    #[getter]
+    pub fn is_regular(&self) -> bool {
+        unsafe {
+            self.font.GetWeight() == DWRITE_FONT_WEIGHT_REGULAR
+                && self.font.GetStyle() == DWRITE_FONT_STYLE_NORMAL
+        }
+    }
+
+    /// Whether this variant is light or thinner, with a weight of at most 300
+    #[getter]
+    pub fn is_light(&self) -> bool {
+        unsafe { self.font.GetWeight() }.0 <= DWRITE_FONT_WEIGHT_LIGHT.0
+    }
+
+    /// The weight, style and stretch of this variant together, as a named tuple ``(weight, style, stretch)``, so they
+    /// can be unpacked in one go: ``weight, style, stretch = variant.classification``
+    ///
+    /// `stretch` is the number from :attr:`stretch_value`.
+    #[getter]
+    pub fn classification(&self, py: Python<'_>) -> PyResult<PyObject> {
+        static CLASSIFICATION: GILOnceCell<PyObject> = GILOnceCell::new();
+        let cls = match CLASSIFICATION.get(py) {
+            Some(cls) => cls,
+            None => {
+                let cls = py
+                    .import("collections")?
+                    .getattr("namedtuple")?
+                    .call1(("Classification", ("weight", "style", "stretch")))?;
+                // Another thread may have got there first, in which case we use its class
+                _ = CLASSIFICATION.set(py, cls.into());
+                CLASSIFICATION.get(py).unwrap()
+            }
+        };
+        cls.call1(py, (self.weight(), self.style(), self.stretch_value()))
+    }
+
+    /// The stretch class of this variant as a number, from 1 (ultra-condensed) to 9 (ultra-expanded), with 5 being
+    /// normal width
+    #[getter]
+    pub fn stretch_value(&self) -> i32 {
+        unsafe { self.font.GetStretch() }.0
+    }
+
+    /// The standard display name of this variant's stretch class, such as ``"Semi Condensed"`` or ``"Normal"``
+    #[getter]
+    pub fn stretch_name(&self) -> &'static str {
+        stretch_display_name(unsafe { self.font.GetStretch() })
+    }
+
+    #[getter]
+    pub fn name(&self) -> Result<String> {
+        if let Some(name) = self.name.get() {
+            return Ok(name.clone());
+        }
+        let name = unsafe { self.font.GetFaceNames()?.get_best_name()? };
+        Ok(self.name.get_or_init(|| name).clone())
+    }
+
+    pub fn __repr__(&self, py: Python) -> PyResult<String> {
+        let family = self.family.as_ref(py);
+
+        Ok(format!(
+            "<FontVariant name={}, family={}, style={} weight={}>",
+            self.name()?,
+            family.repr()?,
+            self.style().into_py(py).as_ref(py).repr()?,
+            self.weight().into_py(py).as_ref(py).repr()?,
+        ))
+    }
+
+    /// Format as one of the variant's names: ``f"{variant}"`` is :attr:`name` (such as ``"Bold"``),
+    /// ``f"{variant:full}"`` the full name (``"Arial Bold"``) and ``f"{variant:ps}"`` the PostScript name
+    /// (``"Arial-BoldMT"``)
+    ///
+    /// Fonts without a full or PostScript name fall back to :attr:`name`.
+    pub fn __format__(&self, spec: &str) -> PyResult<String> {
+        let id = match spec {
+            "" => return Ok(self.name()?),
+            "full" => DWRITE_INFORMATIONAL_STRING_FULL_NAME,
+            "ps" => DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_NAME,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "unknown format code {spec:?} for FontVariant, expected \"full\" or \"ps\""
+                )))
+            }
+        };
+        match self._get_info_string(id)? {
+            Some(name) => Ok(name),
+            None => Ok(self.name()?),
+        }
+    }
+
+    #[getter]
+    pub fn filename(&self) -> PyResult<String> {
+        let names = self.files(false)?;
+        if names.is_empty() {
+            Err(PyValueError::new_err(format!(
+                "{} wasn't loaded from a file, so has no filename",
+                self.name()?
+            )))
+        } else if names.len() != 1 {
+            Err(PyRuntimeError::new_err(
+                "FontVariant had more than one name, please use .files()",
+            ))
+        } else {
+            Ok(names[0].to_owned())
+        }
+    }
+
+    /// The paths of the files this variant is loaded from
+    ///
+    /// Variants loaded from memory (see :meth:`FontSetBuilder.add_bytes`) have no files, so give an empty list.
+    ///
+    /// With `verify`, the variant is checked to be readable from disk instead, raising :class:`FontReadError` if it
+    /// was loaded from memory or naming the first file that doesn't exist. This catches fonts that have been
+    /// uninstalled since the collection was loaded, with a clearer error than whatever later tries to open the file.
+    #[pyo3(text_signature = "($self, verify=False)")]
+    #[args(verify = "false")]
+    pub fn files(&self, verify: bool) -> PyResult<Vec<String>> {
+        let res = unsafe { self._get_files() }?;
+        if verify {
+            if res.is_empty() {
+                return Err(FontReadError::new_err(format!(
+                    "{} was loaded from memory, so has no font file (use read_bytes() to get its data)",
+                    self.name()?
+                )));
+            }
+            if let Some(missing) = res.iter().find(|path| !Path::new(path).exists()) {
+                return Err(FontReadError::new_err((
+                    2,
+                    format!(
+                        "font file for {} doesn't exist (was the font uninstalled?)",
+                        self.name()?
+                    ),
+                    missing.clone(),
+                )));
+            }
+        }
+        Ok(res)
+    }
+
+    /// Like :attr:`filename`, but as a :class:`pathlib.Path`
+    #[getter]
+    pub fn file_path<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let path_cls = py.import("pathlib")?.getattr("Path")?;
+        path_cls.call1((self.filename()?,))
+    }
+
+    /// Like :meth:`files`, but as a list of :class:`pathlib.Path` objects
+    pub fn file_paths<'p>(&self, py: Python<'p>) -> PyResult<Vec<&'p PyAny>> {
+        let path_cls = py.import("pathlib")?.getattr("Path")?;
+        self.files(false)?
+            .into_iter()
+            .map(|name| path_cls.call1((name,)))
+            .collect()
+    }
+
+    /// Whether this variant is part of the system font collection (i.e. installed on this machine)
+    #[getter]
+    pub fn is_installed(&self) -> PyResult<bool> {
+        let collection =
+            FontCollection::get_system_font_collection().map_err(WindowsFontError::from)?;
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        // Only DWRITE_E_NOFONT means the face isn't in the collection, anything else is a real failure
+        match unsafe { collection.GetFontFromFontFace(&face) } {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == DWRITE_E_NOFONT => Ok(false),
+            Err(e) => Err(WindowsFontError::from(e).into()),
+        }
+    }
+
+    /// Whether this variant is installed just for the current user (``"user"``, supported since Windows 10 1809) or
+    /// for every user of the machine (``"machine"``)
+    ///
+    /// This is worked out from the folder the font file is in, so it is `None` for fonts that aren't in either fonts
+    /// folder, such as ones loaded with a :class:`FontSetBuilder`.
+    #[getter]
+    pub fn install_scope(&self) -> PyResult<Option<&'static str>> {
+        if self.source()? != "system" {
+            return Ok(None);
+        }
+        Ok(self
+            .files(false)?
+            .first()
+            .and_then(|path| install_scope_for_path(path)))
+    }
+
+    /// Where this variant was loaded from: ``"system"`` for installed fonts, ``"file"`` for other font files on disk
+    /// (such as from :meth:`FontSetBuilder.add_file`) and ``"memory"`` for fonts loaded from ``bytes``
+    ///
+    /// Fonts from a loader this library doesn't know about give ``"unknown"``.
+    #[getter]
+    pub fn source(&self) -> PyResult<&'static str> {
+        if self.is_installed()? {
+            return Ok("system");
+        }
+        let loader = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| Self::_get_font_files(&face))
+                .map_err(WindowsFontError::from)?
+                .into_iter()
+                .flatten()
+                .next()
+                .map(|file| file.GetLoader())
+                .transpose()
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(match loader {
+            Some(l) if l.cast::<IDWriteLocalFontFileLoader>().is_ok() => "file",
+            Some(l) if l.cast::<IDWriteInMemoryFontFileLoader>().is_ok() => "memory",
+            _ => "unknown",
+        })
+    }
+
+    /// The index of this face within its font file. Only non-zero for collection (`.ttc`) files
+    #[getter]
+    pub fn face_index(&self) -> PyResult<u32> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        Ok(unsafe { face.GetIndex() })
+    }
+
+    /// The complete contents of the font file containing this variant
+    ///
+    /// For a collection (``.ttc``) file this is the whole collection; use :attr:`face_index` to pick the face out of it.
+    #[pyo3(text_signature = "($self)")]
+    pub fn read_bytes<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        let data = unsafe {
+            let face = self.font.CreateFontFace().map_err(WindowsFontError::from)?;
+            let files = Self::_get_font_files(&face).map_err(WindowsFontError::from)?;
+            match files.first() {
+                Some(Some(file)) => tables::read_font_file(file).map_err(WindowsFontError::from)?,
+                _ => return Err(PyOSError::new_err("font variant has no font file")),
+            }
+        };
+        Ok(PyBytes::new(py, &data))
+    }
+
+    /// The font data and face index, ready for use with HarfBuzz
+    ///
+    /// Usage with uharfbuzz: ``face = hb.Face(*variant.hb_face())``
+    #[pyo3(text_signature = "($self)")]
+    pub fn hb_face<'p>(&self, py: Python<'p>) -> PyResult<(&'p PyBytes, u32)> {
+        Ok((self.read_bytes(py)?, self.face_index()?))
+    }
+
+    /// This variant as a WOFF2 file, for serving as a web font
+    ///
+    /// Only this face is included, even if it comes from a collection. The tables are compressed as they are, so the
+    /// result is larger than a dedicated WOFF2 tool would produce, but usually around half the size of the font file.
+    ///
+    /// Raises :class:`PermissionError` if the font's embedding permissions (``fsType`` in its ``OS/2`` table) don't
+    /// allow it to be embedded, either because it is restricted or because only its bitmaps may be embedded.
+    #[pyo3(text_signature = "($self)")]
+    pub fn to_woff2<'p>(&self, py: Python<'p>) -> PyResult<&'p PyBytes> {
+        self._check_embedding(false)?;
+        let data = self.read_bytes(py)?.as_bytes();
+        let (flavor, font_tables) = tables::sfnt_tables(data, self.face_index()?)
+            .ok_or_else(|| PyValueError::new_err("font file is not a valid OpenType font"))?;
+        Ok(PyBytes::new(py, &woff2::encode(flavor, &font_tables)))
+    }
+
+    /// A font containing only the glyphs for `codepoints`, for embedding in a PDF or serving as a web font
+    ///
+    /// `codepoints` is an iterable of code points, as ``int`` or single character ``str`` (so a ``str`` of sample text
+    /// works too). Characters the font doesn't have are left out. The result is the bytes of a TrueType font file
+    /// holding just this face, with the same glyph IDs as this font, so text already shaped with it can be drawn with
+    /// the subset. The OpenType layout tables (and so ligatures and kerning) are dropped.
+    ///
+    /// Only fonts with TrueType outlines can be subset; others raise :class:`ValueError`. Raises
+    /// :class:`PermissionError` if the font's embedding permissions don't allow it to be embedded, or don't allow it to
+    /// be subset.
+    #[pyo3(text_signature = "($self, codepoints)")]
+    pub fn strip_to_subset<'p>(&self, py: Python<'p>, codepoints: &PyAny) -> PyResult<&'p PyBytes> {
+        self._check_embedding(true)?;
+        let text = codepoints
+            .iter()?
+            .map(|item| {
+                let item = item?;
+                match item.extract::<u32>() {
+                    Ok(codepoint) => char::from_u32(codepoint).ok_or_else(|| {
+                        PyValueError::new_err(format!("{:#x} is not a valid code point", codepoint))
+                    }),
+                    Err(_) => item.extract::<char>(),
+                }
+            })
+            .collect::<PyResult<String>>()?;
+        let glyphs = self.open_face()?.glyph_indices(&text)?;
+        let mapping: Vec<(u32, u16)> = text.chars().map(|c| c as u32).zip(glyphs).collect();
+
+        let data = self.read_bytes(py)?.as_bytes();
+        let (_, font_tables) = tables::sfnt_tables(data, self.face_index()?)
+            .ok_or_else(|| PyValueError::new_err("font file is not a valid OpenType font"))?;
+        if !font_tables.iter().any(|(tag, _)| tag == b"glyf") {
+            return Err(PyValueError::new_err(format!(
+                "{} does not have TrueType outlines, so can't be subset",
+                self.name()?
+            )));
+        }
+        let font = subset::subset(&font_tables, &mapping)
+            .ok_or_else(|| PyValueError::new_err("font file is not a valid TrueType font"))?;
+        Ok(PyBytes::new(py, &font))
+    }
+
+    /// A JSON document describing this variant, for storing in a font index or database
+    ///
+    /// The object has the keys ``family``, ``name``, ``weight`` (an object with the numeric ``value`` and the
+    /// :class:`Weight` ``name``, or ``null`` if the weight isn't one of the named ones), ``style`` and ``stretch`` (as
+    /// CSS keywords), ``files``, ``face_index``, ``postscript_name`` and ``information`` (every entry of
+    /// :attr:`information`).
+    #[pyo3(text_signature = "($self)")]
+    pub fn to_json(&self, py: Python<'_>) -> PyResult<String> {
+        let (weight, stretch, style) = unsafe {
+            (
+                self.font.GetWeight(),
+                self.font.GetStretch(),
+                self.font.GetStyle(),
+            )
+        };
+        let information: serde_json::Map<String, serde_json::Value> = self
+            .information()
+            ._items()
+            .map(|(key, val)| (key.to_owned(), val.into()))
+            .collect();
+
+        let doc = serde_json::json!({
+            "family": self.family.borrow(py).name()?,
+            "name": self.name()?,
+            "weight": {
+                "value": weight.0,
+                "name": enums::Weight::from_value(weight.0).map(|w| w.name()),
+            },
+            "style": css_style_name(style),
+            "stretch": css_stretch_name(stretch),
+            "files": self.files(false)?,
+            "face_index": self.face_index()?,
+            "postscript_name": self._get_info_string(DWRITE_INFORMATIONAL_STRING_POSTSCRIPT_NAME)?,
+            "information": information,
+        });
+        Ok(doc.to_string())
+    }
+
+    /// Keyword arguments to open this variant with Pillow's ``ImageFont.truetype``
+    ///
+    /// Usage: ``ImageFont.truetype(**variant.pillow_spec(size=24))``
+    ///
+    /// There is no ``variation`` key for variable fonts, as ``truetype`` has no such argument and would reject it. Set
+    /// the axes on the loaded font instead::
+    ///
+    ///     font = ImageFont.truetype(**variant.pillow_spec(size=24))
+    ///     font.set_variation_by_axes([variant.axis_value(tag) for tag, *_ in variant.design_axis_ranges()])
+    #[pyo3(text_signature = "($self, size=10)")]
+    #[args(size = "10")]
+    pub fn pillow_spec<'p>(&self, size: u32, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let dict = PyDict::new(py);
+        dict.set_item("font", self.filename()?)?;
+        dict.set_item("index", self.face_index()?)?;
+        dict.set_item("size", size)?;
+        Ok(dict)
+    }
+
+    /// Keyword arguments to construct a matplotlib ``FontProperties`` for this variant
+    ///
+    /// Usage: ``FontProperties(**variant.matplotlib_fontproperties())``
+    pub fn matplotlib_fontproperties<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let (weight, stretch, style) = unsafe {
+            (
+                self.font.GetWeight(),
+                self.font.GetStretch(),
+                self.font.GetStyle(),
+            )
+        };
+
+        let dict = PyDict::new(py);
+        dict.set_item("fname", self.filename()?)?;
+        dict.set_item("weight", weight.0)?;
+        // matplotlib uses the CSS names for style and stretch
+        dict.set_item("style", css_style_name(style))?;
+        dict.set_item("stretch", css_stretch_name(stretch))?;
+        Ok(dict)
+    }
+
+    /// The simulations DirectWrite applies to this variant, as a set containing any of ``"bold"`` and ``"oblique"``
+    #[getter]
+    pub fn simulations<'p>(&self, py: Python<'p>) -> PyResult<&'p PyFrozenSet> {
+        PyFrozenSet::new(py, &self._simulation_names())
+    }
+
+    /// A hashable key identifying the family of this variant: its collection and its index within that collection
+    ///
+    /// Variants of the same family have equal keys even when their family names are ambiguous, so this is a reliable way
+    /// to group variants by family, for example with :func:`itertools.groupby`.
+    ///
+    /// The collection part of the key is the address of the collection, which each variant keeps alive. Keys are only
+    /// meaningful while the variants they came from are alive: once every variant (and family and collection object)
+    /// from a collection is gone, a new collection may get the same address. Finding the index can mean searching the
+    /// collection the first time, after which the key is cached on the variant.
+    #[getter]
+    pub fn family_key(&self) -> Result<(usize, u32)> {
+        if let Some(key) = self.family_key.get() {
+            return Ok(*key);
+        }
+        let key = unsafe { self._family_key() }?;
+        Ok(*self.family_key.get_or_init(|| key))
+    }
+
+    /// Whether `other` is exactly the same face as this one: the same face index of the same font file (as loaded by
+    /// the same loader), with the same simulations
+    ///
+    /// Unlike ``==``, which compares by name, this tells apart two fonts that have the same names but come from
+    /// different files.
+    #[pyo3(text_signature = "($self, other)")]
+    pub fn same_face(&self, other: &FontVariant) -> PyResult<bool> {
+        let identity = unsafe { self._face_identity() }.map_err(WindowsFontError::from)?;
+        let other_identity = unsafe { other._face_identity() }.map_err(WindowsFontError::from)?;
+        Ok(identity == other_identity)
+    }
+
+    /// Support for :func:`copy.copy`. The copy has its own font reference and its own :class:`FontFamily` object
+    pub fn __copy__(&self, py: Python<'_>) -> PyResult<FontVariant> {
+        let family = self.family.borrow(py).clone();
+        Ok(FontVariant::new(
+            Rc::new((*self.font).clone()),
+            Py::new(py, family)?,
+        ))
+    }
+
+    /// Support for :func:`copy.deepcopy`. There is no nested state to copy, so this is the same as :meth:`__copy__`
+    #[pyo3(text_signature = "($self, memo)")]
+    pub fn __deepcopy__(&self, _memo: &PyDict, py: Python<'_>) -> PyResult<FontVariant> {
+        self.__copy__(py)
+    }
+
+    /// Support for :mod:`pickle`: the path of this variant's font file, its face index, and a list of the names of its
+    /// :attr:`simulations`
+    ///
+    /// Unpickling opens the face straight from that file, as :func:`open_font` does, rather than looking the font up
+    /// in the system collection, so a pickled variant can still be loaded if the font has since been uninstalled, as
+    /// long as its file is still there. This means the restored variant's :attr:`family` only contains that one
+    /// variant, and its :attr:`source` is ``"file"`` unless the file is an installed font.
+    ///
+    /// Variants loaded from memory have no file to reopen, so raise ``TypeError``.
+    pub fn __getstate__(&self) -> PyResult<(String, u32, Vec<&'static str>)> {
+        if self.files(false)?.is_empty() {
+            return Err(PyTypeError::new_err(
+                "cannot pickle a FontVariant loaded from memory",
+            ));
+        }
+        Ok((
+            self.filename()?,
+            self.face_index()?,
+            self._simulation_names(),
+        ))
+    }
+
+    /// Restore the state returned by :meth:`__getstate__`, opening the face from its file
+    #[pyo3(text_signature = "($self, state)")]
+    pub fn __setstate__(
+        &mut self,
+        state: (PathBuf, u32, Vec<String>),
+        py: Python<'_>,
+    ) -> PyResult<()> {
+        let (path, face_index, names) = state;
+        *self = builder::open_pickled(path, face_index, names, py)?;
+        Ok(())
+    }
+
+    /// Support for :mod:`pickle`. The variant is recreated by opening the face straight from the state returned by
+    /// :meth:`__getstate__`, so the file is only opened once
+    pub fn __reduce__<'p>(&self, py: Python<'p>) -> PyResult<(&'p PyAny, PyObject)> {
+        let open_pickled = py
+            .import("windows_fonts._windows_fonts")?
+            .getattr("_open_pickled")?;
+        Ok((open_pickled, self.__getstate__()?.into_py(py)))
+    }
+
+    /// A copy of this variant with synthetic bold and/or oblique simulations applied
+    ///
+    /// This lets you get, for instance, a bold face for a family that only has a regular one. Passing `False` for both
+    /// gives the variant without any simulations.
+    #[pyo3(text_signature = "($self, bold=False, oblique=False)")]
+    #[args(bold = "false", oblique = "false")]
+    pub fn clone_with_simulations(
+        &self,
+        bold: bool,
+        oblique: bool,
+        py: Python<'_>,
+    ) -> PyResult<FontVariant> {
+        let mut simulations = DWRITE_FONT_SIMULATIONS_NONE.0;
+        if bold {
+            simulations |= DWRITE_FONT_SIMULATIONS_BOLD.0;
+        }
+        if oblique {
+            simulations |= DWRITE_FONT_SIMULATIONS_OBLIQUE.0;
+        }
+
+        let font = unsafe {
+            (|| -> Result<IDWriteFont> {
+                let face = self.font.CreateFontFace()?;
+                let files = Self::_get_font_files(&face)?;
+                let factory: IDWriteFactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
+                let simulated = factory.CreateFontFace(
+                    face.GetType(),
+                    &files,
+                    face.GetIndex(),
+                    DWRITE_FONT_SIMULATIONS(simulations),
+                )?;
+                // Look the face up in the collection this variant came from, which may not be the system one
+                let collection = self.font.GetFontFamily()?.GetFontCollection()?;
+                Ok(collection.GetFontFromFontFace(&simulated)?)
+            })()
+            .map_err(WindowsFontError::from)?
+        };
+
+        Ok(FontVariant::new(Rc::new(font), self.family.clone_ref(py)))
+    }
+
+    /// The range of each design axis of a variable font, as a list of ``(tag, min, default, max)`` tuples
+    ///
+    /// For example ``[("wght", 100.0, 400.0, 900.0), ...]``. Static (non-variable) fonts return an empty list.
+    ///
+    /// Needs Windows 10 April 2018 Update (1803) or above.
+    pub fn design_axis_ranges(&self) -> PyResult<Vec<(String, f32, f32, f32)>> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        let face5 = face.cast::<IDWriteFontFace5>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 17134 or above".to_owned(),
+            )
+        })?;
+
+        unsafe {
+            let resource = face5.GetFontResource().map_err(WindowsFontError::from)?;
+            if !resource.HasVariations().as_bool() {
+                return Ok(Vec::new());
+            }
+
+            let count = resource.GetFontAxisCount() as usize;
+            let mut defaults = vec![DWRITE_FONT_AXIS_VALUE::default(); count];
+            let mut ranges = vec![DWRITE_FONT_AXIS_RANGE::default(); count];
+            resource
+                .GetDefaultFontAxisValues(&mut defaults)
+                .map_err(WindowsFontError::from)?;
+            resource
+                .GetFontAxisRanges(&mut ranges)
+                .map_err(WindowsFontError::from)?;
+
+            // Both lists are in the same (font defined) axis order
+            Ok(ranges
+                .iter()
+                .zip(defaults.iter())
+                .map(|(range, default)| {
+                    (
+                        tables::tag_to_string(range.axisTag.0),
+                        range.minValue,
+                        default.value,
+                        range.maxValue,
+                    )
+                })
+                .collect())
+        }
+    }
+
+    /// The value of the design axis `tag` (such as ``"opsz"``) for this variant of a variable font
+    ///
+    /// Returns `None` if the font doesn't have that axis, or isn't a variable font.
+    #[pyo3(text_signature = "($self, tag)")]
+    pub fn axis_value(&self, tag: &str) -> PyResult<Option<f32>> {
+        let tag = tables::tag_from_str(tag)
+            .ok_or_else(|| PyValueError::new_err(format!("{tag:?} is not a four character tag")))?;
+        Ok(self._face_axis_value(DWRITE_FONT_AXIS_TAG(tag), true))
+    }
+
+    /// Where this variant sits along each design axis of its variable font, normalized to ``0.0`` (the axis minimum)
+    /// to ``1.0`` (the axis maximum), as a dict of tag to position
+    ///
+    /// This is handy for driving sliders in a user interface. The ranges are the ones from
+    /// :meth:`design_axis_ranges`; axes which don't vary have a position of ``0.0``. Static (non-variable) fonts
+    /// return an empty dict.
+    ///
+    /// Needs Windows 10 April 2018 Update (1803) or above.
+    #[pyo3(text_signature = "($self)")]
+    pub fn design_coordinates<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let dict = PyDict::new(py);
+        for (range, value) in self._variable_axes()? {
+            let span = range.maxValue - range.minValue;
+            let position = if span > 0.0 {
+                ((value - range.minValue) / span).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            dict.set_item(tables::tag_to_string(range.axisTag.0), position)?;
+        }
+        Ok(dict)
+    }
+
+    /// The axis values of this variant as a CSS ``font-variation-settings`` value, such as ``'"wght" 550, "wdth" 85'``
+    ///
+    /// Only axes which vary are included, so static (non-variable) fonts give an empty string.
+    ///
+    /// Needs Windows 10 April 2018 Update (1803) or above.
+    #[pyo3(text_signature = "($self)")]
+    pub fn font_variation_settings(&self) -> PyResult<String> {
+        Ok(self
+            ._variable_axes()?
+            .into_iter()
+            .filter(|(range, _)| range.maxValue > range.minValue)
+            .map(|(range, value)| {
+                format!("\"{}\" {}", tables::tag_to_string(range.axisTag.0), value)
+            })
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+
+    /// The set of glyph image formats present in this font
+    ///
+    /// Possible members are ``"truetype"``, ``"cff"``, ``"colr"``, ``"svg"``, ``"png"``, ``"jpeg"``, ``"tiff"`` and
+    /// ``"premultiplied_b8g8r8a8"``. Any of the last six mean the font has color glyphs that need a color-aware render
+    /// path.
+    ///
+    /// Needs Windows 10 Anniversary Update (1607) or above.
+    pub fn glyph_image_formats<'p>(&self, py: Python<'p>) -> PyResult<&'p PyFrozenSet> {
+        const FORMATS: [(DWRITE_GLYPH_IMAGE_FORMATS, &str); 8] = [
+            (DWRITE_GLYPH_IMAGE_FORMATS_TRUETYPE, "truetype"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_CFF, "cff"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_COLR, "colr"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_SVG, "svg"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_PNG, "png"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_JPEG, "jpeg"),
+            (DWRITE_GLYPH_IMAGE_FORMATS_TIFF, "tiff"),
+            (
+                DWRITE_GLYPH_IMAGE_FORMATS_PREMULTIPLIED_B8G8R8A8,
+                "premultiplied_b8g8r8a8",
+            ),
+        ];
+
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        let face4 = face.cast::<IDWriteFontFace4>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 14393 or above".to_owned(),
+            )
+        })?;
+        let formats = unsafe { face4.GetGlyphImageFormats2() };
+
+        let names: Vec<&str> = FORMATS
+            .iter()
+            .filter(|(flag, _)| formats.0 & flag.0 != 0)
+            .map(|(_, name)| *name)
+            .collect();
+        PyFrozenSet::new(py, &names)
+    }
+
+    /// The sizes, in pixels per em, of the bitmap strikes embedded in this font
+    ///
+    /// These come from the `EBLC`, `CBLC` and `sbix` tables, and are sorted with duplicates removed. Purely outline
+    /// fonts return an empty list.
+    #[pyo3(text_signature = "($self)")]
+    pub fn embedded_bitmap_sizes(&self) -> PyResult<Vec<u16>> {
+        let mut sizes = Vec::new();
+        for tag in [b"EBLC", b"CBLC"].iter() {
+            if let Some(ppems) = self._with_table(u32::from_le_bytes(**tag), |t| {
+                tables::bitmap_location_ppems(t.data())
+            })? {
+                sizes.extend(ppems);
+            }
+        }
+        if let Some(ppems) = self._with_table(u32::from_le_bytes(*b"sbix"), |t| {
+            tables::sbix_ppems(t.data())
+        })? {
+            sizes.extend(ppems);
+        }
+        sizes.sort_unstable();
+        sizes.dedup();
+        Ok(sizes)
+    }
+
+    /// The embedded bitmap strike size (see :meth:`embedded_bitmap_sizes`) closest to `target_px` pixels per em, or
+    /// `None` for fonts with no bitmap strikes
+    ///
+    /// Bitmap strikes only look right at their own size, so this is the size to render at for crisp text. When two
+    /// sizes are equally close, the larger is picked.
+    #[pyo3(text_signature = "($self, target_px)")]
+    pub fn best_size(&self, target_px: f32) -> PyResult<Option<u16>> {
+        let distance = |size: u16| (size as f32 - target_px).abs();
+        Ok(self
+            .embedded_bitmap_sizes()?
+            .into_iter()
+            // Sizes are sorted, so on a tie `min_by` keeps the first, smaller, size. Reverse to prefer the larger
+            .rev()
+            .min_by(|&a, &b| distance(a).total_cmp(&distance(b))))
+    }
+
+    /// Read a string straight from the font's `name` table, for the given name ID and platform
+    ///
+    /// Unlike :attr:`information`, this doesn't let DirectWrite pick a record, which helps with old fonts whose
+    /// Windows (platform 3) and Macintosh (platform 1) names disagree. `encoding` and `language` narrow down the
+    /// record further; without a `language` the US English record is preferred, then the first one found. Returns
+    /// `None` if there's no such record.
+    ///
+    /// Macintosh names can only be decoded from the Roman encoding (0), other encodings raise ``ValueError``.
+    #[pyo3(text_signature = "($self, name_id, platform=3, encoding=None, language=None)")]
+    #[args(platform = "3")]
+    pub fn name_record(
+        &self,
+        name_id: u16,
+        platform: u16,
+        encoding: Option<u16>,
+        language: Option<u16>,
+    ) -> PyResult<Option<String>> {
+        // The language IDs for US English on the Windows and Macintosh platforms respectively
+        let english = if platform == 1 { 0 } else { 0x409 };
+
+        self._with_table(u32::from_le_bytes(*b"name"), |t| {
+            let records: Vec<_> = tables::name_records(t.data())
+                .into_iter()
+                .filter(|r| {
+                    r.name_id == name_id
+                        && r.platform_id == platform
+                        && encoding.iter().all(|&e| r.encoding_id == e)
+                        && language.iter().all(|&l| r.language_id == l)
+                })
+                .collect();
+            let record = match records
+                .iter()
+                .find(|r| r.language_id == english)
+                .or_else(|| records.first())
+            {
+                Some(r) => r,
+                None => return Ok(None),
+            };
+            record.decode().map(Some).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "can't decode names in platform {} encoding {}",
+                    record.platform_id, record.encoding_id
+                ))
+            })
+        })?
+        .transpose()
+        .map(Option::flatten)
+    }
+
+    /// The subtables of the font's character to glyph mapping (``cmap``) table, as a list of
+    /// ``(platform_id, encoding_id, format)`` tuples in the order they appear in the font
+    ///
+    /// This is useful for working out why characters aren't found in a font, for instance one that only has a
+    /// Macintosh (platform 1) or symbol (platform 3, encoding 0) mapping.
+    #[pyo3(text_signature = "($self)")]
+    pub fn cmap_subtables(&self) -> PyResult<Vec<(u16, u16, u16)>> {
+        Ok(self
+            ._with_table(u32::from_le_bytes(*b"cmap"), |t| {
+                tables::cmap_subtables(t.data())
+            })?
+            .unwrap_or_default())
+    }
+
+    /// The OpenType script tags (such as ``"latn"``, ``"arab"`` or ``"deva"``) that this font has shaping features
+    /// for, from its ``GSUB`` and ``GPOS`` tables
+    ///
+    /// Scripts such as Arabic and Devanagari need these features to be displayed correctly, so this tells whether text
+    /// in them will actually shape properly, which :meth:`unicode_ranges` alone doesn't. ``"DFLT"`` is the fallback
+    /// used for scripts that aren't listed.
+    #[pyo3(text_signature = "($self)")]
+    pub fn supported_scripts<'p>(&self, py: Python<'p>) -> PyResult<&'p PyFrozenSet> {
+        let mut scripts = Vec::new();
+        for tag in [*b"GSUB", *b"GPOS"].iter() {
+            if let Some(tags) = self._with_table(u32::from_le_bytes(*tag), |t| {
+                tables::layout_script_tags(t.data())
+            })? {
+                scripts.extend(tags);
+            }
+        }
+        PyFrozenSet::new(py, &scripts)
+    }
+
+    /// The raw bytes of the OpenType table `tag` (for example ``"head"`` or ``"OS/2"``), or `None` if the font doesn't
+    /// have that table
+    #[pyo3(text_signature = "($self, tag)")]
+    pub fn get_table(&self, tag: &str, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.open_face()?.get_table(tag, py)
+    }
+
+    /// The name of glyph `glyph_id` (such as ``"A"`` or ``"uni20AC"``) from the font's ``post`` table, or `None` if the
+    /// font doesn't name its glyphs
+    ///
+    /// Glyph IDs can be found with :meth:`FontFace.glyph_indices`.
+    #[pyo3(text_signature = "($self, glyph_id)")]
+    pub fn glyph_name(&self, glyph_id: u16) -> PyResult<Option<String>> {
+        Ok(self
+            ._with_table(u32::from_le_bytes(*b"post"), |t| {
+                tables::post_glyph_name(t.data(), glyph_id)
+            })?
+            .flatten())
+    }
+
+    /// Open the font face of this variant once, for doing many glyph or table level operations on it
+    ///
+    /// Each of the equivalent methods on this class creates the face afresh.
+    #[pyo3(text_signature = "($self)")]
+    pub fn open_face(&self) -> PyResult<face::FontFace> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        Ok(face::FontFace::new(face))
+    }
+
+    /// When this font was created, according to its `head` table
+    #[getter]
+    pub fn created<'p>(&self, py: Python<'p>) -> PyResult<Option<&'p PyAny>> {
+        self._get_head_timestamp(20, py)
+    }
+
+    /// When this font was last modified, according to its `head` table
+    #[getter]
+    pub fn modified<'p>(&self, py: Python<'p>) -> PyResult<Option<&'p PyAny>> {
+        self._get_head_timestamp(28, py)
+    }
+
+    /// A set of strings naming the boolean traits of this variant
+    ///
+    /// Possible members are ``"monospaced"``, ``"symbol"``, ``"color"``, ``"variable"``, ``"simulated_bold"`` and
+    /// ``"simulated_oblique"``. Traits that can't be determined on this version of Windows are never included.
+    #[getter]
+    pub fn flags<'p>(&self, py: Python<'p>) -> PyResult<&'p PyFrozenSet> {
+        let mut flags = Vec::<&str>::new();
+        unsafe {
+            if self.font.IsSymbolFont().as_bool() {
+                flags.push("symbol");
+            }
+            if let Ok(font1) = self.font.cast::<IDWriteFont1>() {
+                if font1.IsMonospacedFont().as_bool() {
+                    flags.push("monospaced");
+                }
+            }
+            if let Ok(font2) = self.font.cast::<IDWriteFont2>() {
+                if font2.IsColorFont().as_bool() {
+                    flags.push("color");
+                }
+            }
+            let simulations = self.font.GetSimulations();
+            if simulations.0 & DWRITE_FONT_SIMULATIONS_BOLD.0 != 0 {
+                flags.push("simulated_bold");
+            }
+            if simulations.0 & DWRITE_FONT_SIMULATIONS_OBLIQUE.0 != 0 {
+                flags.push("simulated_oblique");
+            }
+            let face = self.font.CreateFontFace().map_err(WindowsFontError::from)?;
+            if let Ok(face5) = face.cast::<IDWriteFontFace5>() {
+                if face5.HasVariations().as_bool() {
+                    flags.push("variable");
+                }
+            }
+        }
+        PyFrozenSet::new(py, &flags)
+    }
+
+    /// Whether this looks like an EUDC (end-user-defined character) font rather than a font meant for general use
+    ///
+    /// EUDC fonts only exist to supply private-use characters to other fonts, so font pickers will usually want to
+    /// skip them. This is a heuristic: it checks for the ``.tte`` file extension used by EUDC fonts, and for an
+    /// ``EUDC`` family name.
+    #[getter]
+    pub fn is_eudc(&self) -> PyResult<bool> {
+        let by_file = self.files(false)?.iter().any(|name| {
+            Path::new(name)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("tte"))
+        });
+        if by_file {
+            return Ok(true);
+        }
+        let family_name = self._get_info_string(DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES)?;
+        Ok(family_name.is_some_and(|name| name.to_ascii_uppercase().starts_with("EUDC")))
+    }
+
+    /// Whether this looks like a broken or placeholder font with nothing usable in it, so font pickers can hide it
+    ///
+    /// This is a heuristic: it is true when the font has no glyphs other than ``.notdef``, or doesn't map any
+    /// characters to glyphs at all.
+    #[getter]
+    pub fn is_empty_font(&self) -> PyResult<bool> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        if unsafe { face.GetGlyphCount() } <= 1 {
+            return Ok(true);
+        }
+        Ok(self.unicode_ranges()?.is_empty())
+    }
+
+    /// Whether this variant has outlines (TrueType or CFF) and so can be drawn at any size, rather than only having
+    /// embedded bitmaps at fixed sizes
+    ///
+    /// Uses the glyph image formats DirectWrite reports where available (Windows 10 1607 and above), and otherwise
+    /// checks for a ``glyf``, ``CFF `` or ``CFF2`` table.
+    #[getter]
+    pub fn is_scalable(&self) -> PyResult<bool> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        if let Ok(face4) = face.cast::<IDWriteFontFace4>() {
+            let formats = unsafe { face4.GetGlyphImageFormats2() };
+            let outlines = DWRITE_GLYPH_IMAGE_FORMATS_TRUETYPE.0 | DWRITE_GLYPH_IMAGE_FORMATS_CFF.0;
+            return Ok(formats.0 & outlines != 0);
+        }
+        for tag in [b"glyf", b"CFF ", b"CFF2"].iter() {
+            let table = unsafe { FontTable::get(&face, u32::from_le_bytes(**tag)) }
+                .map_err(WindowsFontError::from)?;
+            if table.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Whether this variant is set up for vertical (top to bottom) layout, as many CJK fonts are
+    ///
+    /// This is true when the font has vertical glyph variants (the ``vert`` or ``vrt2`` features, which swap in rotated
+    /// forms of punctuation and the like) or its own vertical metrics (``vhea`` and ``vmtx`` tables). Fonts without
+    /// either can still be laid out vertically, but with glyphs and metrics DirectWrite makes up for them.
+    #[getter]
+    pub fn has_vertical_metrics(&self) -> PyResult<bool> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        if let Ok(face1) = face.cast::<IDWriteFontFace1>() {
+            if unsafe { face1.HasVerticalGlyphVariants() }.as_bool() {
+                return Ok(true);
+            }
+        }
+        for tag in [b"vhea", b"vmtx"].iter() {
+            let table = unsafe { FontTable::get(&face, u32::from_le_bytes(**tag)) }
+                .map_err(WindowsFontError::from)?;
+            if table.is_none() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// The designer's name for the style of this variant, such as ``"Condensed Medium Italic"``, suitable for showing in
+    /// a style picker
+    ///
+    /// This is the typographic subfamily name (name ID 17) if the font has one, otherwise the Win32 subfamily name (name
+    /// ID 2), in the best matching locale. It is the same as the second item of :meth:`typographic_names`.
+    #[getter]
+    pub fn style_name(&self) -> Result<Option<String>> {
+        Ok(self.typographic_names()?.1)
+    }
+
+    /// The ``(family, subfamily)`` names as the font's designer named them, e.g. ``("Segoe UI", "Semilight")``
+    ///
+    /// These come from the typographic names in the font, falling back to the Win32 family and subfamily names for
+    /// fonts without typographic names. Either name is `None` if neither record is present.
+    #[pyo3(text_signature = "($self)")]
+    pub fn typographic_names(&self) -> Result<(Option<String>, Option<String>)> {
+        let family =
+            match self._get_info_string(DWRITE_INFORMATIONAL_STRING_TYPOGRAPHIC_FAMILY_NAMES)? {
+                Some(name) => Some(name),
+                None => self._get_info_string(DWRITE_INFORMATIONAL_STRING_WIN32_FAMILY_NAMES)?,
+            };
+        let subfamily =
+            match self._get_info_string(DWRITE_INFORMATIONAL_STRING_TYPOGRAPHIC_SUBFAMILY_NAMES)? {
+                Some(name) => Some(name),
+                None => self._get_info_string(DWRITE_INFORMATIONAL_STRING_WIN32_SUBFAMILY_NAMES)?,
+            };
+        Ok((family, subfamily))
+    }
+
+    /// The family name under the weight-stretch-style family model, or `None` if not available
+    ///
+    /// This is the model that groups, for instance, "Arial Narrow" separately from "Arial".
+    #[getter]
+    pub fn weight_stretch_style_family_name(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_WEIGHT_STRETCH_STYLE_FAMILY_NAME)
+    }
+
+    /// The script/language tags (such as ``"Thai"`` or ``"Latn"``) this font was designed for, from its ``meta``
+    /// table
+    ///
+    /// A font can include glyphs for many more languages than it was designed for. The list is empty if the font
+    /// doesn't say.
+    #[getter]
+    pub fn designed_for(&self) -> Result<Vec<String>> {
+        let strings = match self
+            .font
+            .get_info_string(DWRITE_INFORMATIONAL_STRING_DESIGN_SCRIPT_LANGUAGE_TAG)?
+        {
+            Some(strings) => strings,
+            None => return Ok(Vec::new()),
+        };
+        let mut tags: Vec<String> = Vec::new();
+        // Each string can hold several comma separated tags
+        for string in unsafe { strings.get_all_strings() }? {
+            for tag in string.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                if !tags.iter().any(|t| t == tag) {
+                    tags.push(tag.to_owned());
+                }
+            }
+        }
+        Ok(tags)
+    }
+
+    /// The designer of this font, or `None` if the font doesn't say
+    #[getter]
+    pub fn designer(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_DESIGNER)
+    }
+
+    /// The manufacturer (foundry) of this font, or `None` if the font doesn't say
+    #[getter]
+    pub fn manufacturer(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_MANUFACTURER)
+    }
+
+    /// The copyright notice of this font, or `None` if the font doesn't have one
+    #[getter]
+    pub fn copyright(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_COPYRIGHT_NOTICE)
+    }
+
+    /// The trademark notice of this font, or `None` if the font doesn't have one
+    #[getter]
+    pub fn trademark(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_TRADEMARK)
+    }
+
+    /// The version of this font as given in its ``name`` table (name ID 5), such as ``"Version 7.00"``, or `None` if
+    /// the font doesn't say
+    #[getter]
+    pub fn version_string(&self) -> Result<Option<String>> {
+        self._get_info_string(DWRITE_INFORMATIONAL_STRING_VERSION_STRINGS)
+    }
+
+    /// The four character code of the foundry that made this font, from the ``achVendID`` field of the ``OS/2`` table
+    /// (for example ``"MS"`` or ``"ADBE"``), or `None` if the font doesn't have one
+    #[getter]
+    pub fn vendor_id(&self) -> PyResult<Option<String>> {
+        Ok(self
+            ._with_table(u32::from_le_bytes(*b"OS/2"), |t| {
+                tables::os2_vendor_id(t.data())
+            })?
+            .flatten())
+    }
+
+    #[getter]
+    pub fn information(&self) -> InformationDict {
+        InformationDict::new(self.font.clone())
+    }
+
+    /// The design metrics of this font variant, in font design units
+    #[getter]
+    pub fn metrics(&self) -> FontMetrics {
+        let mut metrics = DWRITE_FONT_METRICS::default();
+        unsafe { self.font.GetMetrics(&mut metrics) };
+        metrics.into()
+    }
+
+    /// The design metrics of this font variant, plus the glyph bounding box, sub/superscript positions and caret slope
+    ///
+    /// The extra values are `None` if the font face doesn't support ``IDWriteFontFace1`` (Windows 7 without the
+    /// Platform Update).
+    #[getter]
+    pub fn metrics_extended(&self) -> PyResult<ExtendedMetrics> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        let extended = face.cast::<IDWriteFontFace1>().ok().map(|face1| {
+            let mut metrics = DWRITE_FONT_METRICS1::default();
+            let mut caret = DWRITE_CARET_METRICS::default();
+            unsafe {
+                face1.GetMetrics2(&mut metrics);
+                face1.GetCaretMetrics(&mut caret);
+            }
+            (metrics, caret)
+        });
+        let base = match extended {
+            Some((metrics, _)) => metrics.Base,
+            None => {
+                let mut metrics = DWRITE_FONT_METRICS::default();
+                unsafe { face.GetMetrics(&mut metrics) };
+                metrics
+            }
+        };
+        Ok(ExtendedMetrics::new(base, extended))
+    }
+
+    /// The design metrics of this font variant converted to pixels, for a font size of `size_px` pixels per em
+    ///
+    /// Use :attr:`metrics` if you need the exact values in font design units.
+    #[pyo3(text_signature = "($self, size_px)")]
+    pub fn scaled_metrics(&self, size_px: f32) -> PyResult<ScaledMetrics> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        Ok(self.metrics().scaled(size_px))
+    }
+
+    /// Like :meth:`scaled_metrics`, but for a font size of `size_pt` points on a display of `dpi` dots per inch
+    ///
+    /// The size in pixels is ``size_pt * dpi / 72``, and is available as the ``size_px`` of the result.
+    #[pyo3(text_signature = "($self, size_pt, dpi=96.0)")]
+    #[args(dpi = "96.0")]
+    pub fn metrics_for_dpi(&self, size_pt: f32, dpi: f32) -> PyResult<ScaledMetrics> {
+        if dpi <= 0.0 {
+            return Err(PyValueError::new_err("dpi must be positive"));
+        }
+        if size_pt <= 0.0 {
+            return Err(PyValueError::new_err("size_pt must be positive"));
+        }
+        self.scaled_metrics(size_pt * dpi / 72.0)
+    }
+
+    /// The ``(offset, thickness)`` of the underline, in pixels for a font size of `size_px` pixels per em
+    ///
+    /// The offset is the distance from the baseline to the top of the underline, and is positive upwards, so it is
+    /// usually negative.
+    #[pyo3(text_signature = "($self, size_px)")]
+    pub fn underline(&self, size_px: f32) -> PyResult<(f32, f32)> {
+        let scaled = self.scaled_metrics(size_px)?;
+        Ok((scaled.underline_position, scaled.underline_thickness))
+    }
+
+    /// The ``(offset, thickness)`` of the strikethrough, in pixels for a font size of `size_px` pixels per em
+    ///
+    /// The offset is the distance from the baseline to the top of the strikethrough, and is positive upwards.
+    #[pyo3(text_signature = "($self, size_px)")]
+    pub fn strikethrough(&self, size_px: f32) -> PyResult<(f32, f32)> {
+        let scaled = self.scaled_metrics(size_px)?;
+        Ok((
+            scaled.strikethrough_position,
+            scaled.strikethrough_thickness,
+        ))
+    }
+
+    /// The subscript and superscript sizes and offsets of this font variant, in font design units
+    #[getter]
+    pub fn vertical_metrics(&self) -> PyResult<VerticalMetrics> {
+        let font1 = self
+            .font
+            .cast::<IDWriteFont1>()
+            .map_err(WindowsFontError::from)?;
+        let mut metrics = DWRITE_FONT_METRICS1::default();
+        unsafe { font1.GetMetrics2(&mut metrics) };
+        Ok(metrics.into())
+    }
+
+    /// The metrics of this font variant as adjusted for GDI-compatible rendering at the given size
+    ///
+    /// `size_px` is the em size in pixels and `dpi` the resolution of the target surface. The values are still in font
+    /// design units, but are rounded the same way GDI would round them when rendering at that size.
+    #[pyo3(text_signature = "($self, size_px, dpi=96.0)")]
+    #[args(dpi = "96.0")]
+    pub fn gdi_compatible_metrics(&self, size_px: f32, dpi: f32) -> PyResult<FontMetrics> {
+        if size_px <= 0.0 || dpi <= 0.0 {
+            return Err(PyValueError::new_err("size_px and dpi must be positive"));
+        }
+        let pixels_per_dip = dpi / 96.0;
+        let metrics = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| {
+                    face.GetGdiCompatibleMetrics(size_px / pixels_per_dip, pixels_per_dip, None)
+                })
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(metrics.into())
+    }
+
+    /// The outline of the glyph for `char` as an SVG ``<path>`` element
+    ///
+    /// The coordinates are for an em square of `size` units, with the origin at the top-left and the baseline placed at
+    /// the font's ascent.
+    #[pyo3(text_signature = "($self, char, size=1.0)")]
+    #[args(size = "1.0")]
+    pub fn outline_svg(&self, char: char, size: f32) -> PyResult<String> {
+        if size <= 0.0 {
+            return Err(PyValueError::new_err("size must be positive"));
+        }
+        self.open_face()?.outline_svg(char, size)
+    }
+
+    /// Whether this font has a glyph for `char`
+    #[pyo3(text_signature = "($self, char)")]
+    pub fn has_character(&self, char: char) -> PyResult<bool> {
+        let has = unsafe { self.font.HasCharacter(char as u32) }.map_err(WindowsFontError::from)?;
+        Ok(has.as_bool())
+    }
+
+    /// The ranges of codepoints this font has glyphs for, as a sorted list of inclusive ``(first, last)`` tuples
+    #[pyo3(text_signature = "($self)")]
+    pub fn unicode_ranges(&self) -> PyResult<Vec<(u32, u32)>> {
+        let font1 = self
+            .font
+            .cast::<IDWriteFont1>()
+            .map_err(WindowsFontError::from)?;
+        let mut count = 0u32;
+        let mut ranges = unsafe {
+            // The first call fails with E_NOT_SUFFICIENT_BUFFER, but tells us how many ranges there are
+            _ = font1.GetUnicodeRanges(None, &mut count);
+            let mut ranges = vec![DWRITE_UNICODE_RANGE::default(); count as usize];
+            font1
+                .GetUnicodeRanges(Some(&mut ranges), &mut count)
+                .map_err(WindowsFontError::from)?;
+            ranges
+        };
+        ranges.truncate(count as usize);
+        Ok(ranges.iter().map(|r| (r.first, r.last)).collect())
+    }
+
+    /// The codepoints in the inclusive range `start` to `end` that this font has glyphs for
+    ///
+    /// This is much faster than calling :meth:`has_character` for each codepoint, as it uses a single lookup of the
+    /// font's :meth:`unicode_ranges`.
+    #[pyo3(text_signature = "($self, start, end)")]
+    pub fn has_characters(&self, start: u32, end: u32) -> PyResult<Vec<u32>> {
+        if start > end {
+            return Err(PyValueError::new_err("start must not be greater than end"));
+        }
+        let mut covered = Vec::new();
+        for (first, last) in self.unicode_ranges()? {
+            let (lo, hi) = (first.max(start), last.min(end));
+            if lo <= hi {
+                covered.extend(lo..=hi);
+            }
+        }
+        Ok(covered)
     }
-}
 
-#[pyclass(module = "windows_fonts", unsendable)]
-struct FontVariant {
-    font: Rc<IDWriteFont>,
-    // Keep the family alive so we can use it in `repr`, but don't create a _rust_ memory cycle
-    #[pyo3(get)]
-    family: Py<FontFamily>,
-}
+    /// The fraction, from 0.0 to 1.0, of the distinct characters of `text` this font has glyphs for
+    ///
+    /// This gives a single score for ranking fonts against some sample text. It uses the font's
+    /// :meth:`unicode_ranges`, so is fast for long text. Empty text gives 1.0.
+    #[pyo3(text_signature = "($self, text)")]
+    pub fn coverage_ratio(&self, text: &str) -> PyResult<f64> {
+        let chars: BTreeSet<u32> = text.chars().map(|c| c as u32).collect();
+        if chars.is_empty() {
+            return Ok(1.0);
+        }
+        // The ranges are sorted, so a binary search finds the only one that could hold each character
+        let ranges = self.unicode_ranges()?;
+        let covered = chars
+            .iter()
+            .filter(|&&c| {
+                let i = ranges.partition_point(|&(_, last)| last < c);
+                ranges.get(i).is_some_and(|&(first, _)| first <= c)
+            })
+            .count();
+        Ok(covered as f64 / chars.len() as f64)
+    }
 
-#[pymethods]
-impl FontVariant {
-    #[getter]
-    pub fn style(&self) -> enums::Style {
-        unsafe { ::std::mem::transmute(self.font.GetStyle().0) }
+    /// The advance width in pixels of each character of `text` at a font size of `size_px` pixels per em
+    ///
+    /// The list has one entry per character. Characters the font doesn't have get the advance of its ``.notdef``
+    /// glyph, as that is what would be drawn in their place. No shaping (kerning, ligatures etc.) is applied.
+    #[pyo3(text_signature = "($self, text, size_px)")]
+    pub fn glyph_advances(&self, text: &str, size_px: f32) -> PyResult<Vec<f32>> {
+        self.open_face()?.glyph_advances(text, size_px)
     }
 
-    #[getter]
-    pub fn weight(&self) -> enums::Weight {
-        unsafe { ::std::mem::transmute(self.font.GetWeight().0) }
+    /// The total advance width in pixels of `text` at a font size of `size_px` pixels per em
+    ///
+    /// By default this is the sum of :meth:`glyph_advances`. With `shaped`, the text is shaped first, the way it would
+    /// be when drawn, so kerning, ligatures and contextual forms (as needed by scripts such as Arabic) are taken into
+    /// account. That is slower, but gives the true width of the rendered text.
+    ///
+    /// `features` is a dict of OpenType feature tags to values to shape with, such as ``{"smcp": 1}`` for small caps
+    /// or ``{"liga": 0}`` to turn off standard ligatures. Passing it implies `shaped`.
+    #[pyo3(text_signature = "($self, text, size_px, shaped=False, features=None)")]
+    #[args(shaped = "false", features = "None")]
+    pub fn glyph_run_width(
+        &self,
+        text: &str,
+        size_px: f32,
+        shaped: bool,
+        features: Option<HashMap<String, u32>>,
+    ) -> PyResult<f32> {
+        self.open_face()?
+            .glyph_run_width(text, size_px, shaped, features)
     }
 
-    #[getter]
-    pub fn name(&self) -> Result<String> {
-        unsafe {
-            let names = self.font.GetFaceNames()?;
-            names.get_best_name()
+    /// Rasterize a single character, anti-aliased and cropped to its inked area, as ``(width, height, alpha)``
+    ///
+    /// `alpha` holds one coverage byte per pixel, row by row. Characters with no ink (such as a space) give
+    /// ``(0, 0, b"")``. Characters the font doesn't have are drawn with its ``.notdef`` glyph.
+    ///
+    /// With `as_buffer`, `alpha` is a read-only ``memoryview`` with a shape of ``(height, width)`` instead of
+    /// ``bytes``, which can be passed to ``numpy.asarray`` without copying.
+    #[pyo3(text_signature = "($self, char, size_px, as_buffer=False)")]
+    #[args(as_buffer = "false")]
+    pub fn render_glyph(
+        &self,
+        char: char,
+        size_px: f32,
+        as_buffer: bool,
+        py: Python<'_>,
+    ) -> PyResult<(usize, usize, PyObject)> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
         }
+        let bitmap = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| render::render_text_alpha(&face, &char.to_string(), size_px))
+                .map_err(WindowsFontError::from)?
+        };
+        let (width, height) = (bitmap.width, bitmap.height);
+        let alpha = if as_buffer {
+            buffer::Bitmap::memoryview(bitmap, py)?
+        } else {
+            PyBytes::new(py, &bitmap.data).into()
+        };
+        Ok((width, height, alpha))
     }
 
-    pub fn __repr__(&self, py: Python) -> PyResult<String> {
-        let family = self.family.as_ref(py);
+    /// Rasterize a run of already shaped glyphs, anti-aliased and cropped to the inked area, as ``(width, height,
+    /// alpha)`` like :meth:`render_glyph`
+    ///
+    /// This is for text shaped by something else, such as HarfBuzz. `advances` is the advance of each glyph in pixels,
+    /// and `offsets` the ``(x, y)`` offset of each glyph from its nominal position in pixels, with y pointing up. Either
+    /// can be `None` to use the font's own advances, or no offsets. Both must have an entry per glyph.
+    ///
+    /// `as_buffer` gives `alpha` as a ``memoryview``, as it does for :meth:`render_glyph`.
+    #[pyo3(text_signature = "($self, glyph_ids, advances, offsets, size_px, as_buffer=False)")]
+    #[args(as_buffer = "false")]
+    pub fn render_run(
+        &self,
+        glyph_ids: Vec<u16>,
+        advances: Option<Vec<f32>>,
+        offsets: Option<Vec<(f32, f32)>>,
+        size_px: f32,
+        as_buffer: bool,
+        py: Python<'_>,
+    ) -> PyResult<(usize, usize, PyObject)> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        if advances
+            .as_ref()
+            .is_some_and(|a| a.len() != glyph_ids.len())
+        {
+            return Err(PyValueError::new_err(
+                "advances must have the same length as glyph_ids",
+            ));
+        }
+        if offsets.as_ref().is_some_and(|o| o.len() != glyph_ids.len()) {
+            return Err(PyValueError::new_err(
+                "offsets must have the same length as glyph_ids",
+            ));
+        }
+        let offsets: Option<Vec<DWRITE_GLYPH_OFFSET>> = offsets.map(|offsets| {
+            offsets
+                .into_iter()
+                .map(|(x, y)| DWRITE_GLYPH_OFFSET {
+                    advanceOffset: x,
+                    ascenderOffset: y,
+                })
+                .collect()
+        });
 
-        Ok(format!(
-            "<FontVariant name={}, family={}, style={} weight={}>",
-            self.name()?,
-            family.repr()?,
-            self.style().into_py(py).as_ref(py).repr()?,
-            self.weight().into_py(py).as_ref(py).repr()?,
-        ))
+        let bitmap = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| {
+                    render::render_run_alpha(
+                        &face,
+                        &glyph_ids,
+                        advances.as_deref(),
+                        offsets.as_deref(),
+                        size_px,
+                    )
+                })
+                .map_err(WindowsFontError::from)?
+        };
+        let (width, height) = (bitmap.width, bitmap.height);
+        let alpha = if as_buffer {
+            buffer::Bitmap::memoryview(bitmap, py)?
+        } else {
+            PyBytes::new(py, &bitmap.data).into()
+        };
+        Ok((width, height, alpha))
     }
 
-    #[getter]
-    pub fn filename(&self) -> PyResult<String> {
-        let names = self.files()?;
-        if names.len() != 1 {
-            Err(PyRuntimeError::new_err(
-                "FontVariant had more than one name, please use .files()",
-            ))
-        } else {
-            Ok(names[0].to_owned())
+    /// The colored layers that make up the glyph for `char` in a color (``COLR``) font, bottom layer first, as a list
+    /// of ``(glyph_index, color)`` tuples
+    ///
+    /// Each `color` is a ``(red, green, blue, alpha)`` tuple from the font's default palette, or `None` where the layer
+    /// should be drawn in the text color. Drawing each layer's glyph in its color, in order, gives the color glyph. For
+    /// characters without color layers, and fonts without color, this is a single layer of the character's own glyph
+    /// with a color of `None`.
+    ///
+    /// Needs Windows 8.1 or above.
+    #[pyo3(text_signature = "($self, char, size_px)")]
+    pub fn color_glyph_run(&self, char: char, size_px: f32) -> PyResult<Vec<ColorLayer>> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        unsafe {
+            let face = self.font.CreateFontFace().map_err(WindowsFontError::from)?;
+            let glyph = face
+                .GetGlyphIndices(&(char as u32), 1)
+                .map_err(WindowsFontError::from)?;
+            let factory: IDWriteFactory2 =
+                DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED).map_err(WindowsFontError::from)?;
+
+            let run = DWRITE_GLYPH_RUN {
+                fontFace: Some(face.clone()),
+                fontEmSize: size_px,
+                glyphCount: 1,
+                glyphIndices: &glyph,
+                glyphAdvances: std::ptr::null(),
+                glyphOffsets: std::ptr::null(),
+                isSideways: BOOL(0),
+                bidiLevel: 0,
+            };
+            let layers = match factory.TranslateColorGlyphRun(
+                0.0,
+                0.0,
+                &run,
+                None,
+                DWRITE_MEASURING_MODE_NATURAL,
+                None,
+                0,
+            ) {
+                Ok(layers) => layers,
+                Err(e) if e.code() == DWRITE_E_NOCOLOR => return Ok(vec![(glyph, None)]),
+                Err(e) => return Err(WindowsFontError::from(e).into()),
+            };
+
+            let to_byte = |c: f32| (c * 255.0).round().clamp(0.0, 255.0) as u8;
+            let mut result = Vec::new();
+            while layers.MoveNext().map_err(WindowsFontError::from)?.as_bool() {
+                let layer = &*layers.GetCurrentRun().map_err(WindowsFontError::from)?;
+                // A palette index of 0xFFFF means the layer is drawn in the text color
+                let color = (layer.paletteIndex != 0xFFFF).then(|| {
+                    let c = layer.runColor;
+                    (to_byte(c.r), to_byte(c.g), to_byte(c.b), to_byte(c.a))
+                });
+                let glyphs = slice::from_raw_parts(
+                    layer.glyphRun.glyphIndices,
+                    layer.glyphRun.glyphCount as usize,
+                );
+                result.extend(glyphs.iter().map(|&glyph| (glyph, color)));
+            }
+            Ok(result)
         }
     }
 
-    pub fn files(&self) -> PyResult<Vec<String>> {
-        let res = unsafe { self._get_files() }?;
-        Ok(res)
+    /// Render `text` as a single line and return it as PNG encoded ``bytes``
+    ///
+    /// Colors are ``(red, green, blue, alpha)`` tuples. The image is cropped to the inked area of the text, plus
+    /// `padding` pixels on every side.
+    ///
+    /// The characters are drawn with the font's nominal glyphs, one per character. With `features`, a dict of OpenType
+    /// feature tags to values like :meth:`glyph_run_width` takes, the text is shaped with those features first, for
+    /// previewing them (``{"smcp": 1}`` for small caps, say).
+    #[pyo3(
+        text_signature = "($self, text, size_px, foreground=(0, 0, 0, 255), background=(255, 255, 255, 255), padding=4, features=None)"
+    )]
+    #[args(
+        foreground = "(0, 0, 0, 255)",
+        background = "(255, 255, 255, 255)",
+        padding = "4",
+        features = "None"
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_to_png<'p>(
+        &self,
+        text: &str,
+        size_px: f32,
+        foreground: (u8, u8, u8, u8),
+        background: (u8, u8, u8, u8),
+        padding: u32,
+        features: Option<HashMap<String, u32>>,
+        py: Python<'p>,
+    ) -> PyResult<&'p PyBytes> {
+        if size_px <= 0.0 {
+            return Err(PyValueError::new_err("size_px must be positive"));
+        }
+        let features = features.map(|f| font_features(Some(f))).transpose()?;
+        let bitmap = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| match &features {
+                    Some(features) => {
+                        let shaped = render::shape(&face, text, size_px, features)?;
+                        render::render_run_alpha(
+                            &face,
+                            &shaped.glyphs,
+                            Some(&shaped.advances),
+                            Some(&shaped.offsets),
+                            size_px,
+                        )
+                    }
+                    None => render::render_text_alpha(&face, text, size_px),
+                })
+                .map_err(WindowsFontError::from)?
+        };
+
+        let pad = padding as usize;
+        let width = bitmap.width + pad * 2;
+        let height = bitmap.height + pad * 2;
+        let mut pixels = Vec::with_capacity(width * height * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let coverage = match (x.checked_sub(pad), y.checked_sub(pad)) {
+                    (Some(bx), Some(by)) if bx < bitmap.width && by < bitmap.height => {
+                        bitmap.data[by * bitmap.width + bx]
+                    }
+                    _ => 0,
+                };
+                pixels.extend_from_slice(&blend_over(foreground, background, coverage));
+            }
+        }
+
+        let png = png::encode_rgba(width as u32, height as u32, &pixels);
+        Ok(PyBytes::new(py, &png))
     }
 
-    #[getter]
-    pub fn information(&self) -> InformationDict {
-        InformationDict {
-            font: self.font.clone(),
+    /// A text art preview of `text` in this font, `width` characters wide, for looking at fonts from a terminal
+    ///
+    /// The text is rendered like :meth:`render_to_png` and scaled to fit, keeping its proportions given that terminal
+    /// characters are about twice as tall as they are wide. Each pixel becomes an ASCII character of matching
+    /// darkness, or with `blocks`, pairs of pixels become Unicode block characters (``"▀"``, ``"▄"`` and ``"█"``) for
+    /// a sharper result. The lines are joined with ``"\n"``, without a trailing newline. Text with no ink gives an
+    /// empty string.
+    #[pyo3(text_signature = "($self, width, text, blocks=False)")]
+    #[args(blocks = "false")]
+    pub fn raster_preview_ascii(&self, width: usize, text: &str, blocks: bool) -> PyResult<String> {
+        if width == 0 {
+            return Err(PyValueError::new_err("width must be positive"));
         }
+        let bitmap = unsafe {
+            self.font
+                .CreateFontFace()
+                .and_then(|face| render::render_text_alpha(&face, text, preview::RENDER_SIZE_PX))
+                .map_err(WindowsFontError::from)?
+        };
+        Ok(preview::text_art(
+            &bitmap.data,
+            bitmap.width,
+            bitmap.height,
+            width,
+            blocks,
+        ))
     }
 
     fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
@@ -635,6 +3521,26 @@ impl FontVariant {
     }
 }
 
+/// Composite `fg`, with its alpha scaled by `coverage`, over `bg` (the "source over" operator)
+fn blend_over(fg: (u8, u8, u8, u8), bg: (u8, u8, u8, u8), coverage: u8) -> [u8; 4] {
+    let src_a = (fg.3 as f32 / 255.0) * (coverage as f32 / 255.0);
+    let dst_a = bg.3 as f32 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let channel = |s: u8, d: u8| -> u8 {
+        let c = (s as f32 * src_a + d as f32 * dst_a * (1.0 - src_a)) / out_a;
+        c.round().clamp(0.0, 255.0) as u8
+    };
+    [
+        channel(fg.0, bg.0),
+        channel(fg.1, bg.1),
+        channel(fg.2, bg.2),
+        (out_a * 255.0).round() as u8,
+    ]
+}
+
 impl PartialEq for FontVariant {
     fn eq(&self, other: &Self) -> bool {
         // Quick checks first
@@ -653,12 +3559,171 @@ impl PartialEq for FontVariant {
 }
 
 impl FontVariant {
-    unsafe fn _get_files(&self) -> Result<Vec<String>> {
-        let face = self.font.CreateFontFace()?;
+    /// The range of each design axis of this variable font, with this variant's value on it. The value is the minimum of
+    /// the range for any axis the face doesn't report. Static fonts have no axes
+    fn _variable_axes(&self) -> PyResult<Vec<(DWRITE_FONT_AXIS_RANGE, f32)>> {
+        let face = unsafe { self.font.CreateFontFace() }.map_err(WindowsFontError::from)?;
+        let face5 = face.cast::<IDWriteFontFace5>().map_err(|_| {
+            WindowsFontError::Windows10Needed(
+                "Use of this function requires Windows 10 Build 17134 or above".to_owned(),
+            )
+        })?;
+
+        unsafe {
+            let resource = face5.GetFontResource().map_err(WindowsFontError::from)?;
+            if !resource.HasVariations().as_bool() {
+                return Ok(Vec::new());
+            }
+
+            let mut ranges =
+                vec![DWRITE_FONT_AXIS_RANGE::default(); resource.GetFontAxisCount() as usize];
+            resource
+                .GetFontAxisRanges(&mut ranges)
+                .map_err(WindowsFontError::from)?;
+            let mut values =
+                vec![DWRITE_FONT_AXIS_VALUE::default(); face5.GetFontAxisValueCount() as usize];
+            face5
+                .GetFontAxisValues(&mut values)
+                .map_err(WindowsFontError::from)?;
+
+            Ok(ranges
+                .into_iter()
+                .map(|range| {
+                    let value = values
+                        .iter()
+                        .find(|v| v.axisTag == range.axisTag)
+                        .map_or(range.minValue, |v| v.value);
+                    (range, value)
+                })
+                .collect())
+        }
+    }
+
+    /// The value of the given design axis for this variant, or `None` if it can't be determined
+    ///
+    /// Variable fonts (on Windows 10 Build 17134 and above) report their actual axis values. Otherwise the weight and
+    /// width are derived from the font's weight and stretch classes.
+    fn _axis_value(&self, tag: DWRITE_FONT_AXIS_TAG) -> Option<f32> {
+        if let Some(value) = self._face_axis_value(tag, false) {
+            return Some(value);
+        }
+        match tag {
+            DWRITE_FONT_AXIS_TAG_WEIGHT => Some(unsafe { self.font.GetWeight() }.0 as f32),
+            DWRITE_FONT_AXIS_TAG_WIDTH => stretch_to_width(unsafe { self.font.GetStretch() }),
+            _ => None,
+        }
+    }
+
+    /// The value of the given axis as reported by DirectWrite, or `None` before Windows 10 Build 17134
+    ///
+    /// DirectWrite also reports values for the standard axes of static fonts, unless `variable_only` is set.
+    fn _face_axis_value(&self, tag: DWRITE_FONT_AXIS_TAG, variable_only: bool) -> Option<f32> {
+        unsafe {
+            let face5 = self
+                .font
+                .CreateFontFace()
+                .and_then(|face| face.cast::<IDWriteFontFace5>())
+                .ok()?;
+            if variable_only && !face5.HasVariations().as_bool() {
+                return None;
+            }
+            let mut values =
+                vec![DWRITE_FONT_AXIS_VALUE::default(); face5.GetFontAxisValueCount() as usize];
+            face5.GetFontAxisValues(&mut values).ok()?;
+            values.iter().find(|v| v.axisTag == tag).map(|v| v.value)
+        }
+    }
+
+    /// The names of the simulations DirectWrite applies to this variant, for :attr:`simulations` and pickling
+    fn _simulation_names(&self) -> Vec<&'static str> {
+        let simulations = unsafe { self.font.GetSimulations() };
+        let mut names = Vec::new();
+        if simulations.0 & DWRITE_FONT_SIMULATIONS_BOLD.0 != 0 {
+            names.push("bold");
+        }
+        if simulations.0 & DWRITE_FONT_SIMULATIONS_OBLIQUE.0 != 0 {
+            names.push("oblique");
+        }
+        names
+    }
+
+    /// Raise `PermissionError` unless the font's `fsType` allows it to be embedded, and when `subsetting`, subset
+    fn _check_embedding(&self, subsetting: bool) -> PyResult<()> {
+        let fs_type = self
+            ._with_table(u32::from_le_bytes(*b"OS/2"), |t| {
+                tables::os2_fs_type(t.data())
+            })?
+            .flatten()
+            .unwrap_or(0);
+        // The low four bits are the usage permissions, of which 2 is "restricted license". 0x0100 is "no subsetting"
+        // and 0x0200 "bitmap embedding only"
+        if fs_type & 0x000f == 0x0002 || fs_type & 0x0200 != 0 {
+            return Err(PyPermissionError::new_err(format!(
+                "embedding is not permitted for {} (fsType {:#06x})",
+                self.name()?,
+                fs_type
+            )));
+        }
+        if subsetting && fs_type & 0x0100 != 0 {
+            return Err(PyPermissionError::new_err(format!(
+                "subsetting is not permitted for {} (fsType {:#06x})",
+                self.name()?,
+                fs_type
+            )));
+        }
+        Ok(())
+    }
+
+    /// Get the best locale value of an informational string, or `None` if the font doesn't have it
+    fn _get_info_string(&self, id: DWRITE_INFORMATIONAL_STRING_ID) -> Result<Option<String>> {
+        match self.font.get_info_string(id)? {
+            Some(strings) => Ok(Some(unsafe { strings.get_best_name() }?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Call `f` with the OpenType table `tag` of this font, or return `None` if the font doesn't have that table
+    fn _with_table<T>(&self, tag: u32, f: impl FnOnce(&FontTable) -> T) -> PyResult<Option<T>> {
+        unsafe {
+            let face = self.font.CreateFontFace().map_err(WindowsFontError::from)?;
+            let table = FontTable::get(&face, tag).map_err(WindowsFontError::from)?;
+            Ok(table.as_ref().map(f))
+        }
+    }
+
+    /// Read one of the LONGDATETIME timestamps out of the `head` table as a UTC datetime
+    fn _get_head_timestamp<'p>(
+        &self,
+        offset: usize,
+        py: Python<'p>,
+    ) -> PyResult<Option<&'p PyAny>> {
+        let secs = match self
+            ._with_table(u32::from_le_bytes(*b"head"), |t| t.i64_at(offset))?
+            .flatten()
+        {
+            Some(secs) => secs,
+            None => return Ok(None),
+        };
+
+        // The timestamps are seconds since 1904-01-01. We go via a timedelta rather than fromtimestamp as the latter
+        // doesn't support dates before 1970 on Windows.
+        let datetime = py.import("datetime")?;
+        let utc = datetime.getattr("timezone")?.getattr("utc")?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("tzinfo", utc)?;
+        let epoch = datetime
+            .getattr("datetime")?
+            .call((1904, 1, 1), Some(kwargs))?;
+        let delta = datetime.getattr("timedelta")?.call1((0, secs))?;
+        Ok(Some(epoch.call_method1("__add__", (delta,))?))
+    }
+
+    unsafe fn _get_font_files(
+        face: &IDWriteFontFace,
+    ) -> windows::core::Result<Vec<Option<IDWriteFontFile>>> {
         let mut num_files = 0u32;
         face.GetFiles(&mut num_files, None)?;
 
-        let mut filenames: Vec<String> = Vec::with_capacity(num_files as usize);
         let mut font_files: Vec<Option<IDWriteFontFile>> = Vec::with_capacity(num_files as usize);
 
         face.GetFiles(
@@ -666,33 +3731,119 @@ impl FontVariant {
             Some(font_files.spare_capacity_mut() as *mut _ as _),
         )?;
         font_files.set_len(num_files as usize);
+        Ok(font_files)
+    }
+
+    /// Everything that identifies the face behind this variant: the loader and reference key of each of its files, the
+    /// face index and the simulations
+    unsafe fn _face_identity(&self) -> windows::core::Result<FaceIdentity> {
+        Self::_font_identity(&self.font)
+    }
+
+    unsafe fn _font_identity(font: &IDWriteFont) -> windows::core::Result<FaceIdentity> {
+        let face = font.CreateFontFace()?;
+        let mut files = Vec::new();
+        for file in Self::_get_font_files(&face)?.iter().flatten() {
+            let mut key: *mut c_void = std::ptr::null_mut();
+            let mut key_size = 0u32;
+            file.GetReferenceKey(&mut key, &mut key_size)?;
+            // COM guarantees that querying for IUnknown always gives the same pointer for the same object
+            let loader = file.GetLoader()?.cast::<IUnknown>()?.as_raw() as usize;
+            files.push((
+                loader,
+                slice::from_raw_parts(key as *const u8, key_size as usize).to_vec(),
+            ));
+        }
+        Ok((files, face.GetIndex(), face.GetSimulations().0))
+    }
+
+    /// The collection (as its COM identity) and index within it of this variant's family, looked up without caching
+    unsafe fn _family_key(&self) -> Result<(usize, u32)> {
+        let family = self.font.GetFontFamily()?;
+        let collection = family.GetFontCollection()?;
+        let token = collection.cast::<IUnknown>()?.as_raw() as usize;
+        // Family objects aren't cached, so recognise ours by its first font. Comparing font references is much cheaper
+        // than creating faces to compare their files, but needs IDWriteFont3 (Windows 10 Creators Update)
+        let first = family.GetFont(0)?;
+        let first3 = first.cast::<IDWriteFont3>().ok();
+        let first_identity = match first3 {
+            Some(_) => None,
+            None => Some(Self::_font_identity(&first)?),
+        };
+        let is_ours = |index: u32| -> windows::core::Result<bool> {
+            let font = collection.GetFontFamily(index)?.GetFont(0)?;
+            match &first3 {
+                Some(first3) => Ok(first3.Equals(&font).as_bool()),
+                None => Ok(Some(Self::_font_identity(&font)?) == first_identity),
+            }
+        };
+
+        // Looking up by name is quick, but a name can be shared by several families
+        for name in family.GetFamilyNames()?.get_all_strings()? {
+            let mut exists = BOOL(0);
+            let mut index = 0;
+            collection.FindFamilyName(&HSTRING::from(name), &mut index, &mut exists)?;
+            if exists.as_bool() && is_ours(index)? {
+                return Ok((token, index));
+            }
+        }
+        for index in 0..collection.GetFontFamilyCount() {
+            if is_ours(index)? {
+                return Ok((token, index));
+            }
+        }
+        bail!("family not found in its own collection")
+    }
+
+    unsafe fn _get_files(&self) -> Result<Vec<String>> {
+        let face = self.font.CreateFontFace()?;
+        Self::_get_face_file_paths(&face)
+    }
+
+    /// The paths of the files backing `face`. Files that aren't on disk, such as ones loaded from memory, are skipped
+    unsafe fn _get_face_file_paths(face: &IDWriteFontFace) -> Result<Vec<String>> {
+        let font_files = Self::_get_font_files(face)?;
+        let mut filenames: Vec<String> = Vec::with_capacity(font_files.len());
 
         for font_file in font_files.iter().flatten() {
-            let mut ref_key: *const c_void = std::ptr::null();
-            let mut key_size: u32 = 0;
-            font_file.GetReferenceKey(&mut ref_key as *mut _ as _, &mut key_size as *mut _ as _)?;
-
-            let filename = LOCAL_LOADER.with(|cell| -> String {
-                let loader = cell.borrow();
-                let path_len: usize = loader
-                    .GetFilePathLengthFromKey(ref_key, key_size)
-                    .expect("GetFilePathLengthFromKey failed")
-                    as usize;
-
-                let mut buff = Vec::new();
-                buff.resize(path_len + 1, 0);
-
-                // let x = path.as_ptr();
-                loader
-                    .GetFilePathFromKey(ref_key, key_size, buff.as_mut_slice())
-                    .expect("GetFilePathFromKey failed");
-
-                String::from_utf16(slice::from_raw_parts(buff.as_ptr(), path_len)).unwrap()
-            });
-            filenames.push(filename)
+            filenames.extend(Self::_get_file_path(font_file)?)
         }
         Ok(filenames)
     }
+
+    /// The path of a font file, or `None` if it wasn't loaded by the local font file loader (and so has no path)
+    unsafe fn _get_file_path(font_file: &IDWriteFontFile) -> Result<Option<String>> {
+        if font_file
+            .GetLoader()?
+            .cast::<IDWriteLocalFontFileLoader>()
+            .is_err()
+        {
+            return Ok(None);
+        }
+
+        let mut ref_key: *const c_void = std::ptr::null();
+        let mut key_size: u32 = 0;
+        font_file.GetReferenceKey(&mut ref_key as *mut _ as _, &mut key_size as *mut _ as _)?;
+
+        LOCAL_LOADER.with(|cell| -> Result<Option<String>> {
+            let loader = cell.borrow();
+            let loader = match loader.as_ref() {
+                Some(loader) => loader,
+                None => bail!("windows_fonts.shutdown() has been called"),
+            };
+            let path_len: usize = loader.GetFilePathLengthFromKey(ref_key, key_size)? as usize;
+
+            let mut buff = Vec::new();
+            buff.resize(path_len + 1, 0);
+
+            loader.GetFilePathFromKey(ref_key, key_size, buff.as_mut_slice())?;
+
+            Ok(Some(String::from_utf16(slice::from_raw_parts(
+                buff.as_ptr(),
+                path_len,
+            ))?))
+        })
+    }
 }
 
 trait PyInformationStrings {
@@ -727,19 +3878,10 @@ struct InformationIter {
 }
 
 impl InformationIter {
-    pub fn new(font: Rc<IDWriteFont>) -> Self {
-        // Ideally I'd reuse the _valid_information_keys() function, but I couldn't get the borrow checker to be happy with it :(
-        // let clone = font.clone();
-        let rc: Box<dyn Iterator<Item = &'static str>> = Box::new(
-            INFO_STRING_NAMES
-                .entries()
-                .filter_map(move |(key, (id, _))| match font.get_info_string(*id) {
-                    Ok(Some(_)) => Some(*key),
-                    Ok(None) => None,
-                    Err(_) => None,
-                }),
-        );
-        Self { iter: rc }
+    pub fn new(keys: Vec<&'static str>) -> Self {
+        Self {
+            iter: Box::new(keys.into_iter()),
+        }
     }
 }
 
@@ -811,93 +3953,141 @@ static INFO_STRING_NAMES: Map<
 ///
 /// Access can either be a string, or one of the integer constants defined in `DWRITE_INFORMATIONAL_STRING_ID`__
 ///
+/// The set of keys present in the font is looked up the first time it is needed and then cached on this instance, so
+/// repeated calls to ``len()``, ``in``, ``keys()``, ``items()`` etc. don't have to query the font again. Each access of
+/// :attr:`FontVariant.information` returns a new instance with its own cache.
+///
 /// .. __: https://learn.microsoft.com/en-us/windows/win32/api/dwrite/ne-dwrite-dwrite_informational_string_id
 #[pyclass(module = "windows_fonts", unsendable)]
 struct InformationDict {
     font: Rc<IDWriteFont>,
+    present_keys: OnceCell<Vec<(&'static str, DWRITE_INFORMATIONAL_STRING_ID)>>,
 }
 
 impl InformationDict {
-    fn _items(&self) -> Box<dyn Iterator<Item = (&str, String)>> {
-        let clone = self.font.clone();
-
-        Box::new(
-            INFO_STRING_NAMES
-                .entries()
-                .filter_map(move |(key, (id, _))| match clone.get_info_string(*id) {
-                    Ok(Some(local_strings)) => {
-                        Some((*key, unsafe { local_strings.get_best_name().unwrap() }))
-                    }
-                    Ok(None) => None,
-                    Err(_) => None,
-                }),
-        )
+    fn new(font: Rc<IDWriteFont>) -> Self {
+        InformationDict {
+            font,
+            present_keys: OnceCell::new(),
+        }
     }
 
-    fn _valid_information_keys(
-        &self,
-    ) -> Box<dyn Iterator<Item = (&str, DWRITE_INFORMATIONAL_STRING_ID)>> {
-        let clone = self.font.clone();
+    fn _items(&self) -> impl Iterator<Item = (&str, String)> {
+        self._valid_information_keys()
+            .iter()
+            .filter_map(move |(key, id)| match self.font.get_info_string(*id) {
+                Ok(Some(local_strings)) => {
+                    Some((*key, unsafe { local_strings.get_best_name().unwrap() }))
+                }
+                Ok(None) => None,
+                Err(_) => None,
+            })
+    }
 
-        Box::new(
+    fn _valid_information_keys(&self) -> &[(&'static str, DWRITE_INFORMATIONAL_STRING_ID)] {
+        self.present_keys.get_or_init(|| {
             INFO_STRING_NAMES
                 .entries()
-                .filter_map(move |(key, (id, _))| match clone.get_info_string(*id) {
+                .filter_map(|(key, (id, _))| match self.font.get_info_string(*id) {
                     Ok(Some(_)) => Some((*key, *id)),
                     Ok(None) => None,
                     Err(_) => None,
-                }),
-        )
+                })
+                .collect()
+        })
+    }
+}
+
+/// Order strings for showing to a user: case-insensitively, falling back to the exact text to keep the order stable
+fn display_order(a: &str, b: &str) -> std::cmp::Ordering {
+    a.to_lowercase()
+        .cmp(&b.to_lowercase())
+        .then_with(|| a.cmp(b))
+}
+
+impl PartialEq for InformationDict {
+    fn eq(&self, other: &Self) -> bool {
+        // Materialize each side once, rather than looking up every key on both fonts
+        let ours: HashMap<&str, String> = self._items().collect();
+        let theirs: HashMap<&str, String> = other._items().collect();
+        ours == theirs
     }
 }
 
 #[pymethods]
 impl InformationDict {
     pub fn __len__(&self) -> Result<usize> {
-        Ok(self._valid_information_keys().count())
+        Ok(self._valid_information_keys().len())
+    }
+
+    /// Whether the font has any informational strings, stopping at the first one found
+    pub fn __bool__(&self) -> bool {
+        if let Some(keys) = self.present_keys.get() {
+            return !keys.is_empty();
+        }
+        INFO_STRING_NAMES
+            .values()
+            .any(|(id, _)| matches!(self.font.get_info_string(*id), Ok(Some(_))))
     }
 
     pub fn __contains__(&self, key: &PyAny) -> Result<bool> {
         if let Ok(pystr) = key.downcast::<PyString>() {
             let wanted = pystr.to_str()?;
-            Ok(self._valid_information_keys().any(|(key, _)| key == wanted))
+            Ok(self
+                ._valid_information_keys()
+                .iter()
+                .any(|(key, _)| *key == wanted))
         } else if let Ok(pylong) = key.downcast::<PyLong>() {
-            let wanted = pylong.extract()?;
-            Ok(self._valid_information_keys().any(|(_, id)| id.0 == wanted))
+            let wanted: i32 = pylong.extract()?;
+            Ok(self
+                ._valid_information_keys()
+                .iter()
+                .any(|(_, id)| id.0 == wanted))
         } else {
             Ok(false)
         }
     }
 
-    pub fn keys<'p>(&self, py: Python<'p>) -> Result<&'p PyList> {
-        let list = PyList::empty(py);
-        for (key, _id) in self._valid_information_keys() {
-            list.append(PyString::new(py, key))?;
-        }
-        list.sort()?;
-        Ok(list)
+    /// The keys present in this font, sorted case-insensitively
+    pub fn keys<'p>(&self, py: Python<'p>) -> &'p PyList {
+        let mut keys: Vec<&str> = self
+            ._valid_information_keys()
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        keys.sort_by(|a, b| display_order(a, b));
+        PyList::new(py, keys)
     }
 
-    pub fn values<'p>(&self, py: Python<'p>) -> Result<&'p PyList> {
-        let list = PyList::empty(py);
-        for (_key, val) in self._items() {
-            list.append(val.into_py(py))?;
-        }
-        list.sort()?;
-        Ok(list)
+    /// The values present in this font, sorted case-insensitively
+    pub fn values<'p>(&self, py: Python<'p>) -> &'p PyList {
+        let mut values: Vec<String> = self._items().map(|(_, val)| val).collect();
+        values.sort_by(|a, b| display_order(a, b));
+        PyList::new(py, values)
+    }
+
+    /// The ``(key, value)`` pairs present in this font, sorted case-insensitively by key
+    pub fn items<'p>(&self, py: Python<'p>) -> &'p PyList {
+        let mut items: Vec<(&str, String)> = self._items().collect();
+        items.sort_by(|(a, _), (b, _)| display_order(a, b));
+        PyList::new(py, items)
     }
 
-    pub fn items<'p>(&self, py: Python<'p>) -> Result<&'p PyList> {
-        let list = PyList::empty(py);
-        for (key, val) in self._items() {
-            list.append(PyTuple::new(py, vec![key.into_py(py), val.into_py(py)]))?;
+    fn __richcmp__(&self, other: &Self, op: CompareOp, py: Python<'_>) -> PyObject {
+        match op {
+            CompareOp::Eq => self.eq(other).into_py(py),
+            CompareOp::Ne => self.ne(other).into_py(py),
+            _ => py.NotImplemented(),
         }
-        list.sort()?;
-        Ok(list)
     }
 
     pub fn __iter__(slf: PyRef<'_, Self>) -> InformationIter {
-        InformationIter::new(slf.font.clone())
+        let keys = slf
+            ._valid_information_keys()
+            .iter()
+            .map(|(key, _)| *key)
+            .collect();
+        InformationIter::new(keys)
     }
 
     pub fn __getitem__(&self, key: IntOrStr) -> PyResult<String> {
@@ -919,29 +4109,190 @@ impl InformationDict {
 
 /// A Python module implemented in Rust.
 #[pymodule]
-fn _windows_fonts(_py: Python, m: &PyModule) -> PyResult<()> {
+fn _windows_fonts(py: Python, m: &PyModule) -> PyResult<()> {
     m.add_class::<FontCollection>()?;
     // Even though these aren't constructable from python code, for ease of use in type checking we export them anyway
     m.add_class::<FontFamily>()?;
     m.add_class::<FontVariant>()?;
     m.add_class::<InformationDict>()?;
+    m.add_class::<FontMetrics>()?;
+    m.add_class::<builder::FontSetBuilder>()?;
+    m.add_class::<ScaledMetrics>()?;
+    m.add_class::<VerticalMetrics>()?;
+    m.add_class::<ExtendedMetrics>()?;
+    m.add_class::<snapshot::FontSnapshot>()?;
+    m.add_class::<face::FontFace>()?;
     m.add_class::<enums::Weight>()?;
     m.add_class::<enums::Style>()?;
+    m.add("FontReadError", py.get_type::<FontReadError>())?;
 
     m.add_function(wrap_pyfunction!(get_matching_variants, m)?)?;
+    m.add_function(wrap_pyfunction!(iter_matching_variants, m)?)?;
+    m.add_function(wrap_pyfunction!(find_by_wss_family, m)?)?;
+    m.add_function(wrap_pyfunction!(variant_from_face, m)?)?;
+    m.add_function(wrap_pyfunction!(shutdown, m)?)?;
+    m.add_function(wrap_pyfunction!(warm_up, m)?)?;
+    m.add_function(wrap_pyfunction!(reload_locale, m)?)?;
+    m.add_function(wrap_pyfunction!(capabilities::capabilities, m)?)?;
+    m.add_function(wrap_pyfunction!(builder::open_font, m)?)?;
+    m.add_function(wrap_pyfunction!(builder::open_pickled, m)?)?;
+    m.add_function(wrap_pyfunction!(fallback::default_fallback_font, m)?)?;
+    m.add_function(wrap_pyfunction!(generic::families_by_generic, m)?)?;
+    m.add_function(wrap_pyfunction!(system::system_ui_font, m)?)?;
+    m.add_function(wrap_pyfunction!(system::theme_font, m)?)?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use windows::core::{implement, PWSTR};
+
+    /// A localized strings object with no strings in it, as found in some malformed fonts
+    #[implement(IDWriteLocalizedStrings)]
+    struct EmptyLocalizedStrings;
+
+    #[allow(non_snake_case)]
+    impl IDWriteLocalizedStrings_Impl for EmptyLocalizedStrings {
+        fn GetCount(&self) -> u32 {
+            0
+        }
+
+        fn FindLocaleName(
+            &self,
+            _localename: &PCWSTR,
+            _index: *mut u32,
+            exists: *mut BOOL,
+        ) -> windows::core::Result<()> {
+            unsafe { *exists = BOOL(0) };
+            Ok(())
+        }
+
+        fn GetLocaleNameLength(&self, _index: u32) -> windows::core::Result<u32> {
+            Err(windows::Win32::Foundation::E_INVALIDARG.into())
+        }
+
+        fn GetLocaleName(
+            &self,
+            _index: u32,
+            _localename: PWSTR,
+            _size: u32,
+        ) -> windows::core::Result<()> {
+            Err(windows::Win32::Foundation::E_INVALIDARG.into())
+        }
+
+        fn GetStringLength(&self, _index: u32) -> windows::core::Result<u32> {
+            Err(windows::Win32::Foundation::E_INVALIDARG.into())
+        }
+
+        fn GetString(
+            &self,
+            _index: u32,
+            _stringbuffer: PWSTR,
+            _size: u32,
+        ) -> windows::core::Result<()> {
+            Err(windows::Win32::Foundation::E_INVALIDARG.into())
+        }
+    }
+
+    #[test]
+    fn test_best_name_empty_strings() {
+        let strings: IDWriteLocalizedStrings = EmptyLocalizedStrings.into();
+
+        let name = unsafe { strings.get_best_name() }.expect("empty strings should not error");
+        assert_eq!(name, "");
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("Arial", "Arial"), 0);
+        assert_eq!(edit_distance("ariel", "Arial"), 1);
+        assert_eq!(edit_distance("Arial", "Arial Black"), 6);
+        assert_eq!(edit_distance("", "Arial"), 5);
+        assert_eq!(edit_distance("Segoe", "Sego UI"), 3);
+    }
+
+    #[test]
+    fn test_weight_display_name() {
+        assert_eq!(weight_display_name(400), "Regular");
+        assert_eq!(weight_display_name(600), "Semi Bold");
+        assert_eq!(weight_display_name(100), "Thin");
+        assert_eq!(weight_display_name(1), "Thin");
+        assert_eq!(weight_display_name(999), "Extra Black");
+        // Halfway between Semi Light (350) and Regular (400)
+        assert_eq!(weight_display_name(375), "Semi Light");
+    }
+
+    #[test]
+    fn test_stretch_display_name() {
+        assert_eq!(stretch_display_name(DWRITE_FONT_STRETCH_NORMAL), "Normal");
+        assert_eq!(
+            stretch_display_name(DWRITE_FONT_STRETCH_SEMI_CONDENSED),
+            "Semi Condensed"
+        );
+        assert_eq!(
+            stretch_display_name(DWRITE_FONT_STRETCH_ULTRA_EXPANDED),
+            "Ultra Expanded"
+        );
+        assert_eq!(
+            stretch_display_name(DWRITE_FONT_STRETCH_UNDEFINED),
+            "Normal"
+        );
+    }
+
+    #[test]
+    fn test_dwrite0_first_matching_font() {
+        // The fallback for when the font list can't be retrieved should pick the same font as the list would
+        let describe = |font: &IDWriteFont| unsafe {
+            (
+                font.GetFaceNames().unwrap().get_best_name().unwrap(),
+                font.GetWeight(),
+                font.GetStretch(),
+                font.GetStyle(),
+                font.GetSimulations(),
+            )
+        };
+        let collection = FontCollection::get_system_font_collection().unwrap();
+        for name in ["Arial", "Impact"] {
+            let family = unsafe { FontCollection::_find_family(&collection, name) }
+                .unwrap()
+                .unwrap();
+            for (weight, stretch, style) in [
+                (None, None, None),
+                (Some(700.0), None, Some(enums::Style::ITALIIC)),
+                (Some(400.0), Some(DWRITE_FONT_STRETCH_CONDENSED), None),
+                (Some(900.0), None, Some(enums::Style::OBLIQUE)),
+            ] {
+                let first = unsafe {
+                    FontFamily::_dwrite0_first_matching_font(
+                        &family,
+                        weight,
+                        stretch,
+                        style.clone(),
+                    )
+                }
+                .unwrap();
+                let list = unsafe {
+                    family.GetMatchingFonts(
+                        DWRITE_FONT_WEIGHT(weight.unwrap_or(400.0) as i32),
+                        stretch.unwrap_or(DWRITE_FONT_STRETCH_NORMAL),
+                        DWRITE_FONT_STYLE(style.unwrap_or(enums::Style::NORMAL) as i32),
+                    )
+                }
+                .unwrap();
+                assert_eq!(
+                    describe(&first),
+                    describe(&unsafe { list.GetFont(0) }.unwrap())
+                );
+            }
+        }
+    }
 
     #[test]
     fn test_local_loader() {
         // Test that we can actually get a LocalLoader without panicing
         LOCAL_LOADER.with(|f| {
-            f.borrow();
-            /* no-op */
+            assert!(f.borrow().is_some());
         })
     }
 }
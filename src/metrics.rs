@@ -0,0 +1,285 @@
+use pyo3::prelude::*;
+use windows::Win32::Graphics::DirectWrite::{
+    DWRITE_CARET_METRICS, DWRITE_FONT_METRICS, DWRITE_FONT_METRICS1,
+};
+
+/// Overall metrics for a font face, in font design units
+///
+/// See https://learn.microsoft.com/en-us/windows/win32/api/dwrite/ns-dwrite-dwrite_font_metrics for the meaning of
+/// each field
+#[pyclass(module = "windows_fonts")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FontMetrics {
+    #[pyo3(get)]
+    pub design_units_per_em: u16,
+    #[pyo3(get)]
+    pub ascent: u16,
+    #[pyo3(get)]
+    pub descent: u16,
+    #[pyo3(get)]
+    pub line_gap: i16,
+    #[pyo3(get)]
+    pub cap_height: u16,
+    #[pyo3(get)]
+    pub x_height: u16,
+    #[pyo3(get)]
+    pub underline_position: i16,
+    #[pyo3(get)]
+    pub underline_thickness: u16,
+    #[pyo3(get)]
+    pub strikethrough_position: i16,
+    #[pyo3(get)]
+    pub strikethrough_thickness: u16,
+}
+
+impl From<DWRITE_FONT_METRICS> for FontMetrics {
+    fn from(m: DWRITE_FONT_METRICS) -> Self {
+        FontMetrics {
+            design_units_per_em: m.designUnitsPerEm,
+            ascent: m.ascent,
+            descent: m.descent,
+            line_gap: m.lineGap,
+            cap_height: m.capHeight,
+            x_height: m.xHeight,
+            underline_position: m.underlinePosition,
+            underline_thickness: m.underlineThickness,
+            strikethrough_position: m.strikethroughPosition,
+            strikethrough_thickness: m.strikethroughThickness,
+        }
+    }
+}
+
+impl FontMetrics {
+    /// Convert these metrics to pixels for a font size of `size_px` pixels per em
+    pub fn scaled(&self, size_px: f32) -> ScaledMetrics {
+        let scale = size_px / self.design_units_per_em as f32;
+        ScaledMetrics {
+            size_px,
+            ascent: self.ascent as f32 * scale,
+            descent: self.descent as f32 * scale,
+            line_gap: self.line_gap as f32 * scale,
+            line_height: (self.ascent as f32 + self.descent as f32 + self.line_gap as f32) * scale,
+            cap_height: self.cap_height as f32 * scale,
+            x_height: self.x_height as f32 * scale,
+            underline_position: self.underline_position as f32 * scale,
+            underline_thickness: self.underline_thickness as f32 * scale,
+            strikethrough_position: self.strikethrough_position as f32 * scale,
+            strikethrough_thickness: self.strikethrough_thickness as f32 * scale,
+        }
+    }
+}
+
+#[pymethods]
+impl FontMetrics {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "<FontMetrics design_units_per_em={}, ascent={}, descent={}, line_gap={}>",
+            self.design_units_per_em, self.ascent, self.descent, self.line_gap
+        )
+    }
+}
+
+/// The same values as :class:`FontMetrics`, converted to (fractional) pixels for a given font size
+///
+/// `line_height` is the recommended distance between baselines: ``ascent + descent + line_gap``
+#[pyclass(module = "windows_fonts")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScaledMetrics {
+    #[pyo3(get)]
+    pub size_px: f32,
+    #[pyo3(get)]
+    pub ascent: f32,
+    #[pyo3(get)]
+    pub descent: f32,
+    #[pyo3(get)]
+    pub line_gap: f32,
+    #[pyo3(get)]
+    pub line_height: f32,
+    #[pyo3(get)]
+    pub cap_height: f32,
+    #[pyo3(get)]
+    pub x_height: f32,
+    #[pyo3(get)]
+    pub underline_position: f32,
+    #[pyo3(get)]
+    pub underline_thickness: f32,
+    #[pyo3(get)]
+    pub strikethrough_position: f32,
+    #[pyo3(get)]
+    pub strikethrough_thickness: f32,
+}
+
+#[pymethods]
+impl ScaledMetrics {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "<ScaledMetrics size_px={}, ascent={}, descent={}, line_gap={}>",
+            self.size_px, self.ascent, self.descent, self.line_gap
+        )
+    }
+}
+
+/// Subscript and superscript sizes and positions for a font face, in font design units
+///
+/// The offsets are relative to the baseline origin of the normal text, with positive `y` being up (as in the ``OS/2``
+/// table these come from)
+#[pyclass(module = "windows_fonts")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerticalMetrics {
+    #[pyo3(get)]
+    pub subscript_size_x: i16,
+    #[pyo3(get)]
+    pub subscript_size_y: i16,
+    #[pyo3(get)]
+    pub subscript_offset_x: i16,
+    #[pyo3(get)]
+    pub subscript_offset_y: i16,
+    #[pyo3(get)]
+    pub superscript_size_x: i16,
+    #[pyo3(get)]
+    pub superscript_size_y: i16,
+    #[pyo3(get)]
+    pub superscript_offset_x: i16,
+    #[pyo3(get)]
+    pub superscript_offset_y: i16,
+}
+
+impl From<DWRITE_FONT_METRICS1> for VerticalMetrics {
+    fn from(m: DWRITE_FONT_METRICS1) -> Self {
+        VerticalMetrics {
+            subscript_size_x: m.subscriptSizeX,
+            subscript_size_y: m.subscriptSizeY,
+            subscript_offset_x: m.subscriptPositionX,
+            subscript_offset_y: m.subscriptPositionY,
+            superscript_size_x: m.superscriptSizeX,
+            superscript_size_y: m.superscriptSizeY,
+            superscript_offset_x: m.superscriptPositionX,
+            superscript_offset_y: m.superscriptPositionY,
+        }
+    }
+}
+
+#[pymethods]
+impl VerticalMetrics {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "<VerticalMetrics subscript_size=({}, {}), subscript_offset=({}, {}), superscript_size=({}, {}), superscript_offset=({}, {})>",
+            self.subscript_size_x,
+            self.subscript_size_y,
+            self.subscript_offset_x,
+            self.subscript_offset_y,
+            self.superscript_size_x,
+            self.superscript_size_y,
+            self.superscript_offset_x,
+            self.superscript_offset_y
+        )
+    }
+}
+
+/// The values of :class:`FontMetrics` plus the extra metrics from DirectWrite 1.1, in font design units
+///
+/// See https://learn.microsoft.com/en-us/windows/win32/api/dwrite_1/ns-dwrite_1-dwrite_font_metrics1 and
+/// https://learn.microsoft.com/en-us/windows/win32/api/dwrite_1/ns-dwrite_1-dwrite_caret_metrics for the meaning of
+/// each field. The extra fields are `None` where those aren't available.
+#[pyclass(module = "windows_fonts")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtendedMetrics {
+    #[pyo3(get)]
+    pub design_units_per_em: u16,
+    #[pyo3(get)]
+    pub ascent: u16,
+    #[pyo3(get)]
+    pub descent: u16,
+    #[pyo3(get)]
+    pub line_gap: i16,
+    #[pyo3(get)]
+    pub cap_height: u16,
+    #[pyo3(get)]
+    pub x_height: u16,
+    #[pyo3(get)]
+    pub underline_position: i16,
+    #[pyo3(get)]
+    pub underline_thickness: u16,
+    #[pyo3(get)]
+    pub strikethrough_position: i16,
+    #[pyo3(get)]
+    pub strikethrough_thickness: u16,
+    #[pyo3(get)]
+    pub glyph_box_left: Option<i16>,
+    #[pyo3(get)]
+    pub glyph_box_top: Option<i16>,
+    #[pyo3(get)]
+    pub glyph_box_right: Option<i16>,
+    #[pyo3(get)]
+    pub glyph_box_bottom: Option<i16>,
+    #[pyo3(get)]
+    pub subscript_position_x: Option<i16>,
+    #[pyo3(get)]
+    pub subscript_position_y: Option<i16>,
+    #[pyo3(get)]
+    pub superscript_position_x: Option<i16>,
+    #[pyo3(get)]
+    pub superscript_position_y: Option<i16>,
+    #[pyo3(get)]
+    pub has_typographic_metrics: Option<bool>,
+    #[pyo3(get)]
+    pub caret_slope_rise: Option<i16>,
+    #[pyo3(get)]
+    pub caret_slope_run: Option<i16>,
+    #[pyo3(get)]
+    pub caret_offset: Option<i16>,
+}
+
+impl ExtendedMetrics {
+    pub fn new(
+        base: DWRITE_FONT_METRICS,
+        extended: Option<(DWRITE_FONT_METRICS1, DWRITE_CARET_METRICS)>,
+    ) -> Self {
+        let base = FontMetrics::from(base);
+        let (m, caret) = match extended {
+            Some((m, caret)) => (Some(m), Some(caret)),
+            None => (None, None),
+        };
+        ExtendedMetrics {
+            design_units_per_em: base.design_units_per_em,
+            ascent: base.ascent,
+            descent: base.descent,
+            line_gap: base.line_gap,
+            cap_height: base.cap_height,
+            x_height: base.x_height,
+            underline_position: base.underline_position,
+            underline_thickness: base.underline_thickness,
+            strikethrough_position: base.strikethrough_position,
+            strikethrough_thickness: base.strikethrough_thickness,
+            glyph_box_left: m.map(|m| m.glyphBoxLeft),
+            glyph_box_top: m.map(|m| m.glyphBoxTop),
+            glyph_box_right: m.map(|m| m.glyphBoxRight),
+            glyph_box_bottom: m.map(|m| m.glyphBoxBottom),
+            subscript_position_x: m.map(|m| m.subscriptPositionX),
+            subscript_position_y: m.map(|m| m.subscriptPositionY),
+            superscript_position_x: m.map(|m| m.superscriptPositionX),
+            superscript_position_y: m.map(|m| m.superscriptPositionY),
+            has_typographic_metrics: m.map(|m| m.hasTypographicMetrics.as_bool()),
+            caret_slope_rise: caret.map(|c| c.slopeRise),
+            caret_slope_run: caret.map(|c| c.slopeRun),
+            caret_offset: caret.map(|c| c.offset),
+        }
+    }
+}
+
+#[pymethods]
+impl ExtendedMetrics {
+    pub fn __repr__(&self) -> String {
+        format!(
+            "<ExtendedMetrics design_units_per_em={}, ascent={}, descent={}, line_gap={}, glyph_box=({:?}, {:?}, {:?}, {:?})>",
+            self.design_units_per_em,
+            self.ascent,
+            self.descent,
+            self.line_gap,
+            self.glyph_box_left,
+            self.glyph_box_top,
+            self.glyph_box_right,
+            self.glyph_box_bottom
+        )
+    }
+}
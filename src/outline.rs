@@ -0,0 +1,96 @@
+use std::cell::RefCell;
+use std::fmt::Write;
+
+use windows::core::{implement, AsImpl};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::Direct2D::Common::*;
+use windows::Win32::Graphics::DirectWrite::IDWriteFontFace;
+
+/// A geometry sink that records the glyph outline it is given as SVG path data
+#[implement(ID2D1SimplifiedGeometrySink)]
+struct SvgPathSink {
+    path: RefCell<String>,
+    // DirectWrite outlines are relative to the baseline (with y pointing down), this moves them into the em box
+    y_offset: f32,
+}
+
+impl SvgPathSink {
+    fn push_point(&self, cmd: char, points: &[D2D_POINT_2F]) {
+        let mut path = self.path.borrow_mut();
+        if !path.is_empty() {
+            path.push(' ');
+        }
+        path.push(cmd);
+        for p in points {
+            _ = write!(path, " {} {}", fmt_num(p.x), fmt_num(p.y + self.y_offset));
+        }
+    }
+}
+
+// Round to a sensible precision and strip the trailing zeros to keep the path data compact
+fn fmt_num(n: f32) -> String {
+    let s = format!("{:.3}", n);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" {
+        "0".to_owned()
+    } else {
+        s.to_owned()
+    }
+}
+
+#[allow(non_snake_case)]
+impl ID2D1SimplifiedGeometrySink_Impl for SvgPathSink {
+    fn SetFillMode(&self, _fillmode: D2D1_FILL_MODE) {}
+
+    fn SetSegmentFlags(&self, _vertexflags: D2D1_PATH_SEGMENT) {}
+
+    fn BeginFigure(&self, startpoint: &D2D_POINT_2F, _figurebegin: D2D1_FIGURE_BEGIN) {
+        self.push_point('M', &[*startpoint]);
+    }
+
+    fn AddLines(&self, points: *const D2D_POINT_2F, pointscount: u32) {
+        let points = unsafe { std::slice::from_raw_parts(points, pointscount as usize) };
+        for p in points {
+            self.push_point('L', &[*p]);
+        }
+    }
+
+    fn AddBeziers(&self, beziers: *const D2D1_BEZIER_SEGMENT, bezierscount: u32) {
+        let beziers = unsafe { std::slice::from_raw_parts(beziers, bezierscount as usize) };
+        for b in beziers {
+            self.push_point('C', &[b.point1, b.point2, b.point3]);
+        }
+    }
+
+    fn EndFigure(&self, figureend: D2D1_FIGURE_END) {
+        if figureend == D2D1_FIGURE_END_CLOSED {
+            self.path.borrow_mut().push_str(" Z");
+        }
+    }
+
+    fn Close(&self) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+/// Get the outline of a single glyph as SVG path data, for an em box of `size` units square.
+///
+/// `ascent` is the ascent of the font as a fraction of the em size, and is used to place the baseline in the box.
+pub unsafe fn glyph_svg_path(
+    face: &IDWriteFontFace,
+    glyph: u16,
+    size: f32,
+    ascent: f32,
+) -> windows::core::Result<String> {
+    let sink: ID2D1SimplifiedGeometrySink = SvgPathSink {
+        path: RefCell::new(String::new()),
+        y_offset: ascent * size,
+    }
+    .into();
+
+    face.GetGlyphRunOutline(size, &glyph, None, None, 1, BOOL(0), BOOL(0), &sink)?;
+
+    let inner: &SvgPathSink = sink.as_impl();
+    let path = inner.path.borrow().clone();
+    Ok(path)
+}
@@ -0,0 +1,112 @@
+//! Text art previews of rendered text, for looking at fonts from a terminal.
+
+/// Characters from lightest to darkest, for drawing coverage with plain ASCII
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+
+/// The size in pixels per em to render text at before scaling it to fit, big enough to scale down for most previews
+pub const RENDER_SIZE_PX: f32 = 64.0;
+
+/// How much taller than wide a terminal character cell is, roughly
+const CELL_ASPECT: f32 = 2.0;
+
+/// Scale an 8-bit coverage bitmap to `columns` by `rows`, averaging the pixels each new pixel covers
+fn downsample(data: &[u8], width: usize, height: usize, columns: usize, rows: usize) -> Vec<u8> {
+    // Each cell covers at least one pixel, so bitmaps smaller than the output are scaled up rather than left with gaps
+    let span = |i: usize, cells: usize, pixels: usize| {
+        let start = i * pixels / cells;
+        start..((i + 1) * pixels / cells).max(start + 1)
+    };
+    let mut out = Vec::with_capacity(columns * rows);
+    for row in 0..rows {
+        let ys = span(row, rows, height);
+        for column in 0..columns {
+            let xs = span(column, columns, width);
+            let total: usize = ys
+                .clone()
+                .flat_map(|y| data[y * width + xs.start..y * width + xs.end].iter())
+                .map(|&v| v as usize)
+                .sum();
+            out.push((total / (ys.len() * xs.len())) as u8);
+        }
+    }
+    out
+}
+
+/// Draw an 8-bit coverage bitmap as lines of text `columns` characters wide, keeping its aspect ratio
+///
+/// With `blocks`, each character is two pixels stacked vertically, drawn with the Unicode half and full block
+/// characters. Otherwise each character is one pixel, drawn with an ASCII character of matching darkness.
+pub fn text_art(data: &[u8], width: usize, height: usize, columns: usize, blocks: bool) -> String {
+    if width == 0 || height == 0 || columns == 0 {
+        return String::new();
+    }
+    // The number of rows of pixels that keeps the aspect ratio once drawn in character cells
+    let pixel_rows = height as f32 * columns as f32 / width as f32 / CELL_ASPECT;
+    let lines: Vec<String> = if blocks {
+        let rows = ((pixel_rows * 2.0).round() as usize).max(2);
+        let pixels = downsample(data, width, height, columns, rows);
+        pixels
+            .chunks(columns * 2)
+            .map(|pair| {
+                let (top, bottom) = pair.split_at(columns);
+                (0..columns)
+                    .map(|i| {
+                        let bottom = bottom.get(i).is_some_and(|&v| v >= 128);
+                        match (top[i] >= 128, bottom) {
+                            (true, true) => '█',
+                            (true, false) => '▀',
+                            (false, true) => '▄',
+                            (false, false) => ' ',
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    } else {
+        let rows = (pixel_rows.round() as usize).max(1);
+        let pixels = downsample(data, width, height, columns, rows);
+        pixels
+            .chunks(columns)
+            .map(|row| {
+                row.iter()
+                    .map(|&v| {
+                        let level = (v as usize * (ASCII_RAMP.len() - 1) + 127) / 255;
+                        ASCII_RAMP[level] as char
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_downsample() {
+        let data = [0, 255, 255, 255, 0, 0, 0, 0];
+        assert_eq!(downsample(&data, 4, 2, 2, 1), vec![63, 127]);
+        assert_eq!(downsample(&data, 4, 2, 4, 2), data.to_vec());
+        // Scaling up repeats pixels
+        assert_eq!(downsample(&[10, 20], 2, 1, 4, 1), vec![10, 10, 20, 20]);
+    }
+
+    #[test]
+    fn test_text_art_ascii() {
+        // A 4x4 bitmap comes out 4 columns by 2 rows as terminal cells are twice as tall as wide
+        let data = [
+            255, 255, 0, 0, 255, 255, 0, 0, 255, 255, 128, 128, 255, 255, 128, 128,
+        ];
+        assert_eq!(text_art(&data, 4, 4, 4, false), "@@  \n@@++");
+        assert_eq!(text_art(&data, 4, 4, 0, false), "");
+        assert_eq!(text_art(&[], 0, 0, 4, false), "");
+    }
+
+    #[test]
+    fn test_text_art_blocks() {
+        let data = [255, 0, 0, 255, 255, 255, 0, 0];
+        assert_eq!(text_art(&data, 2, 4, 2, true), "▀▄\n▀▀");
+    }
+}
@@ -0,0 +1,341 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use windows::core::{Vtable, PCWSTR};
+use windows::Win32::Foundation::{BOOL, ERROR_INSUFFICIENT_BUFFER};
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::analysis::{ScriptSink, TextSource};
+use crate::USER_LOCALE;
+
+/// Map each character of `text` to a glyph index in the face. Characters the font doesn't have map to glyph 0
+pub unsafe fn glyph_indices(face: &IDWriteFontFace, text: &str) -> windows::core::Result<Vec<u16>> {
+    let codepoints: Vec<u32> = text.chars().map(|c| c as u32).collect();
+    let mut glyphs = vec![0u16; codepoints.len()];
+    // The generated `GetGlyphIndices` wrapper can only return a single glyph, so call through the vtable directly
+    (face.vtable().GetGlyphIndices)(
+        face.as_raw(),
+        codepoints.as_ptr(),
+        codepoints.len() as u32,
+        glyphs.as_mut_ptr(),
+    )
+    .ok()?;
+    Ok(glyphs)
+}
+
+/// The horizontal advance of each glyph, in font design units
+pub unsafe fn design_advances(
+    face: &IDWriteFontFace,
+    glyphs: &[u16],
+) -> windows::core::Result<Vec<u32>> {
+    let mut metrics = vec![DWRITE_GLYPH_METRICS::default(); glyphs.len()];
+    face.GetDesignGlyphMetrics(
+        glyphs.as_ptr(),
+        glyphs.len() as u32,
+        metrics.as_mut_ptr(),
+        false,
+    )?;
+    Ok(metrics.iter().map(|m| m.advanceWidth).collect())
+}
+
+/// The glyphs of some text after shaping, with the advance and offset of each in pixels
+#[derive(Default)]
+pub struct ShapedGlyphs {
+    pub glyphs: Vec<u16>,
+    pub advances: Vec<f32>,
+    pub offsets: Vec<DWRITE_GLYPH_OFFSET>,
+}
+
+/// Shape `text` at `size_px` pixels per em, so with kerning, ligatures and any other contextual substitutions the font
+/// does applied, along with the OpenType `features` (which may turn the default ones off)
+///
+/// There may be fewer (ligatures) or more (decomposition) glyphs than characters. Right-to-left text (such as Arabic or
+/// Hebrew) is shaped as such, and the glyphs are returned in the order they are drawn, left to right.
+pub unsafe fn shape(
+    face: &IDWriteFontFace,
+    text: &str,
+    size_px: f32,
+    features: &[DWRITE_FONT_FEATURE],
+) -> windows::core::Result<ShapedGlyphs> {
+    let wide: Vec<u16> = text.encode_utf16().collect();
+    if wide.is_empty() {
+        return Ok(ShapedGlyphs::default());
+    }
+    let factory: IDWriteFactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
+    let analyzer = factory.CreateTextAnalyzer()?;
+    let mut locale = USER_LOCALE.with(|locale| locale.borrow().as_wide().to_vec());
+    locale.push(0);
+
+    // Shaping is done one script and direction at a time
+    let runs = Rc::new(RefCell::new(Vec::new()));
+    let bidi = Rc::new(RefCell::new(Vec::new()));
+    let source = TextSource::create(wide.clone());
+    let sink = ScriptSink::create(runs.clone(), bidi.clone());
+    analyzer.AnalyzeScript(&source, 0, wide.len() as u32, &sink)?;
+    analyzer.AnalyzeBidi(&source, 0, wide.len() as u32, &sink)?;
+    let mut levels = vec![0u8; wide.len()];
+    for &(position, length, level) in bidi.borrow().iter() {
+        levels[position as usize..(position + length) as usize].fill(level);
+    }
+    let mut runs = split_runs(&runs.borrow(), &levels);
+    runs.sort_by_key(|&(position, _, _, _)| position);
+
+    // The features apply to the whole of each run
+    let mut features = features.to_vec();
+    let typographic = DWRITE_TYPOGRAPHIC_FEATURES {
+        features: features.as_mut_ptr(),
+        featureCount: features.len() as u32,
+    };
+    let typographic: *const DWRITE_TYPOGRAPHIC_FEATURES = &typographic;
+
+    let mut shaped_runs = Vec::with_capacity(runs.len());
+    for &(position, length, analysis, level) in &runs {
+        let run = &wide[position as usize..(position + length) as usize];
+        let right_to_left = level % 2 == 1;
+        let (feature_ranges, range_count) = if features.is_empty() {
+            (None, 0)
+        } else {
+            (Some(&typographic as *const _), 1)
+        };
+        let range_lengths = feature_ranges.map(|_| &length as *const u32);
+        let mut cluster_map = vec![0u16; run.len()];
+        let mut text_props = vec![DWRITE_SHAPING_TEXT_PROPERTIES::default(); run.len()];
+
+        // The recommended first guess at the number of glyphs, growing it if that isn't enough
+        let mut max_glyphs = run.len() * 3 / 2 + 16;
+        let (glyphs, glyph_props) = loop {
+            let mut glyphs = vec![0u16; max_glyphs];
+            let mut glyph_props = vec![DWRITE_SHAPING_GLYPH_PROPERTIES::default(); max_glyphs];
+            let mut count = 0;
+            match analyzer.GetGlyphs(
+                PCWSTR(run.as_ptr()),
+                run.len() as u32,
+                face,
+                false,
+                right_to_left,
+                &analysis,
+                PCWSTR(locale.as_ptr()),
+                None::<&IDWriteNumberSubstitution>,
+                feature_ranges,
+                range_lengths,
+                range_count,
+                max_glyphs as u32,
+                cluster_map.as_mut_ptr(),
+                text_props.as_mut_ptr(),
+                glyphs.as_mut_ptr(),
+                glyph_props.as_mut_ptr(),
+                &mut count,
+            ) {
+                Ok(()) => {
+                    glyphs.truncate(count as usize);
+                    glyph_props.truncate(count as usize);
+                    break (glyphs, glyph_props);
+                }
+                Err(e) if e.code() == ERROR_INSUFFICIENT_BUFFER.to_hresult() => max_glyphs *= 2,
+                Err(e) => return Err(e),
+            }
+        };
+
+        let mut run_advances = vec![0f32; glyphs.len()];
+        let mut offsets = vec![DWRITE_GLYPH_OFFSET::default(); glyphs.len()];
+        analyzer.GetGlyphPlacements(
+            PCWSTR(run.as_ptr()),
+            cluster_map.as_ptr(),
+            text_props.as_mut_ptr(),
+            run.len() as u32,
+            glyphs.as_ptr(),
+            glyph_props.as_ptr(),
+            glyphs.len() as u32,
+            face,
+            size_px,
+            false,
+            right_to_left,
+            &analysis,
+            PCWSTR(locale.as_ptr()),
+            feature_ranges,
+            range_lengths,
+            range_count,
+            run_advances.as_mut_ptr(),
+            offsets.as_mut_ptr(),
+        )?;
+
+        let mut run_shaped = ShapedGlyphs {
+            glyphs,
+            advances: run_advances,
+            offsets,
+        };
+        if right_to_left {
+            // Right-to-left glyphs come back in logical order, with offsets along the direction of the run
+            run_shaped.glyphs.reverse();
+            run_shaped.advances.reverse();
+            run_shaped.offsets.reverse();
+            for offset in &mut run_shaped.offsets {
+                offset.advanceOffset = -offset.advanceOffset;
+            }
+        }
+        shaped_runs.push(run_shaped);
+    }
+
+    // Put the runs in the order they are displayed in, left to right
+    let run_levels: Vec<u8> = runs.iter().map(|&(_, _, _, level)| level).collect();
+    let mut shaped = ShapedGlyphs::default();
+    for index in visual_order(&run_levels) {
+        let run = std::mem::take(&mut shaped_runs[index]);
+        shaped.glyphs.extend(run.glyphs);
+        shaped.advances.extend(run.advances);
+        shaped.offsets.extend(run.offsets);
+    }
+    Ok(shaped)
+}
+
+/// Split the script runs from `AnalyzeScript` wherever the bidi level of the text changes, as
+/// `(position, length, analysis, level)`
+fn split_runs(
+    runs: &[(u32, u32, DWRITE_SCRIPT_ANALYSIS)],
+    levels: &[u8],
+) -> Vec<(u32, u32, DWRITE_SCRIPT_ANALYSIS, u8)> {
+    let mut split = Vec::new();
+    for &(position, length, analysis) in runs {
+        let mut start = position;
+        for i in position..position + length {
+            if i + 1 == position + length || levels[i as usize + 1] != levels[i as usize] {
+                split.push((start, i + 1 - start, analysis, levels[start as usize]));
+                start = i + 1;
+            }
+        }
+    }
+    split
+}
+
+/// The order to display runs with the given bidi levels in, left to right, as indices into `levels`
+///
+/// This is rule L2 of the Unicode bidi algorithm: from the highest level down to the lowest odd one, reverse every
+/// sequence of runs at that level or above.
+fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let highest = levels.iter().copied().max().unwrap_or(0);
+    let lowest_odd = levels
+        .iter()
+        .copied()
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] >= level {
+                let start = i;
+                while i < order.len() && levels[order[i]] >= level {
+                    i += 1;
+                }
+                order[start..i].reverse();
+            } else {
+                i += 1;
+            }
+        }
+    }
+    order
+}
+
+/// An 8-bit coverage (alpha) bitmap, one byte per pixel, row by row
+pub struct AlphaBitmap {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+/// Rasterize a single line of text at `size_px` pixels per em, anti-aliased, cropped to the inked area
+pub unsafe fn render_text_alpha(
+    face: &IDWriteFontFace,
+    text: &str,
+    size_px: f32,
+) -> windows::core::Result<AlphaBitmap> {
+    let glyphs = glyph_indices(face, text)?;
+    render_run_alpha(face, &glyphs, None, None, size_px)
+}
+
+/// Rasterize a run of glyphs at `size_px` pixels per em, anti-aliased, cropped to the inked area
+///
+/// `advances` and `offsets` are in pixels, and must have an entry per glyph. Without them the font's own (nominal)
+/// advances are used, with no offsets.
+pub unsafe fn render_run_alpha(
+    face: &IDWriteFontFace,
+    glyphs: &[u16],
+    advances: Option<&[f32]>,
+    offsets: Option<&[DWRITE_GLYPH_OFFSET]>,
+    size_px: f32,
+) -> windows::core::Result<AlphaBitmap> {
+    let factory: IDWriteFactory = DWriteCreateFactory(DWRITE_FACTORY_TYPE_SHARED)?;
+
+    let run = DWRITE_GLYPH_RUN {
+        fontFace: Some(face.clone()),
+        fontEmSize: size_px,
+        glyphCount: glyphs.len() as u32,
+        glyphIndices: glyphs.as_ptr(),
+        glyphAdvances: advances.map_or(std::ptr::null(), |a| a.as_ptr()),
+        glyphOffsets: offsets.map_or(std::ptr::null(), |o| o.as_ptr()),
+        isSideways: BOOL(0),
+        bidiLevel: 0,
+    };
+    let analysis = factory.CreateGlyphRunAnalysis(
+        &run,
+        1.0,
+        None,
+        DWRITE_RENDERING_MODE_CLEARTYPE_NATURAL_SYMMETRIC,
+        DWRITE_MEASURING_MODE_NATURAL,
+        0.0,
+        0.0,
+    )?;
+
+    let bounds = analysis.GetAlphaTextureBounds(DWRITE_TEXTURE_CLEARTYPE_3x1)?;
+    let width = (bounds.right - bounds.left).max(0) as usize;
+    let height = (bounds.bottom - bounds.top).max(0) as usize;
+    if width == 0 || height == 0 {
+        return Ok(AlphaBitmap {
+            width: 0,
+            height: 0,
+            data: Vec::new(),
+        });
+    }
+
+    // The ClearType texture has a value per sub-pixel. Averaging them gives a plain grayscale coverage value
+    let mut texture = vec![0u8; width * height * 3];
+    analysis.CreateAlphaTexture(DWRITE_TEXTURE_CLEARTYPE_3x1, &bounds, &mut texture)?;
+    let data = texture
+        .chunks_exact(3)
+        .map(|rgb| ((rgb[0] as u16 + rgb[1] as u16 + rgb[2] as u16) / 3) as u8)
+        .collect();
+
+    Ok(AlphaBitmap {
+        width,
+        height,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visual_order() {
+        assert_eq!(visual_order(&[]), Vec::<usize>::new());
+        assert_eq!(visual_order(&[0, 0]), vec![0, 1]);
+        // Right-to-left text in a left-to-right paragraph
+        assert_eq!(visual_order(&[0, 1, 1, 0]), vec![0, 2, 1, 3]);
+        // Numbers (level 2) inside right-to-left text keep their own order
+        assert_eq!(visual_order(&[1, 2, 2, 1]), vec![3, 1, 2, 0]);
+    }
+
+    #[test]
+    fn test_split_runs() {
+        let analysis = DWRITE_SCRIPT_ANALYSIS::default();
+        let runs = [(0, 3, analysis), (3, 2, analysis)];
+        let levels = [0, 1, 1, 1, 0];
+        let split: Vec<_> = split_runs(&runs, &levels)
+            .into_iter()
+            .map(|(position, length, _, level)| (position, length, level))
+            .collect();
+        assert_eq!(split, vec![(0, 1, 0), (1, 2, 1), (3, 1, 1), (4, 1, 0)]);
+    }
+}
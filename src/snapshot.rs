@@ -0,0 +1,98 @@
+use std::rc::Rc;
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use windows::Win32::Graphics::DirectWrite::*;
+
+use crate::enums::Style;
+use crate::errors::WindowsFontError;
+use crate::{BestLocaleName, FontVariant, InformationDict};
+
+/// A plain copy of the metadata of a single font, detached from DirectWrite
+///
+/// Unlike :class:`FontVariant`, snapshots can be passed to and used from other threads (for instance in a
+/// ``ThreadPoolExecutor``). Create them with :meth:`FontCollection.snapshot`.
+///
+/// `files` only includes fonts stored on disk, so is empty for fonts loaded from ``bytes``.
+#[pyclass(module = "windows_fonts", frozen)]
+#[derive(Clone)]
+pub struct FontSnapshot {
+    #[pyo3(get)]
+    family: String,
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    weight: i32,
+    #[pyo3(get)]
+    style: Style,
+    #[pyo3(get)]
+    stretch: i32,
+    #[pyo3(get)]
+    files: Vec<String>,
+    #[pyo3(get)]
+    face_index: u32,
+    // In the same order as `InformationDict`
+    information: Vec<(String, String)>,
+}
+
+impl FontSnapshot {
+    pub unsafe fn new(font: &IDWriteFont) -> anyhow::Result<Self> {
+        let face = font.CreateFontFace()?;
+        let mut files = Vec::new();
+        for file in FontVariant::_get_font_files(&face)?.iter().flatten() {
+            // Only the local loader can tell us a path
+            files.extend(FontVariant::_get_file_path(file)?);
+        }
+
+        Ok(FontSnapshot {
+            family: font.GetFontFamily()?.GetFamilyNames()?.get_best_name()?,
+            name: font.GetFaceNames()?.get_best_name()?,
+            weight: font.GetWeight().0,
+            style: font.GetStyle().into(),
+            stretch: font.GetStretch().0,
+            files,
+            face_index: face.GetIndex(),
+            information: InformationDict::new(Rc::new(font.clone()))
+                ._items()
+                .map(|(key, val)| (key.to_owned(), val))
+                .collect(),
+        })
+    }
+}
+
+/// Snapshot every font in `collection`
+pub fn snapshot_collection(collection: &IDWriteFontCollection1) -> PyResult<Vec<FontSnapshot>> {
+    let mut snapshots = Vec::new();
+    unsafe {
+        for i in 0..collection.GetFontFamilyCount() {
+            let family = collection
+                .GetFontFamily(i)
+                .map_err(WindowsFontError::from)?;
+            for j in 0..family.GetFontCount() {
+                let font = family.GetFont(j).map_err(WindowsFontError::from)?;
+                snapshots.push(FontSnapshot::new(&font)?);
+            }
+        }
+    }
+    Ok(snapshots)
+}
+
+#[pymethods]
+impl FontSnapshot {
+    /// The informational strings of the font, like :attr:`FontVariant.information` but as a plain ``dict``
+    #[getter]
+    pub fn information<'p>(&self, py: Python<'p>) -> PyResult<&'p PyDict> {
+        let dict = PyDict::new(py);
+        for (key, val) in &self.information {
+            dict.set_item(key, val)?;
+        }
+        Ok(dict)
+    }
+
+    pub fn __repr__(&self) -> String {
+        format!(
+            "<FontSnapshot name={}, family={:?}, weight={}, stretch={}>",
+            self.name, self.family, self.weight, self.stretch
+        )
+    }
+}
@@ -0,0 +1,447 @@
+//! Subsetting of a font with TrueType outlines down to the glyphs needed for some characters, for embedding.
+//!
+//! Glyph IDs aren't renumbered: glyphs that aren't needed are left empty, and the font is cut off after the last
+//! glyph that is. The hinting tables are kept, but the OpenType layout tables (`GSUB`, `GPOS`, `kern` etc.) are
+//! dropped along with everything else not listed here, as they could refer to glyphs which are no longer there.
+
+use std::collections::BTreeSet;
+
+use crate::tables::{u16_at, u32_at, SfntTable};
+
+/// Tables which are copied into the subset unchanged, if the font has them
+const COPIED_TABLES: [&[u8; 4]; 6] = [b"OS/2", b"name", b"cvt ", b"fpgm", b"prep", b"gasp"];
+
+/// The sfnt version of a font with TrueType outlines
+const TRUETYPE_VERSION: u32 = 0x00010000;
+
+// Flags of each component of a composite glyph
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// The longest a format 4 `cmap` subtable can be, as its length is 16 bits
+const MAX_FORMAT_4_LENGTH: usize = 0xffff;
+
+fn round4(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+fn find_table<'a>(tables: &[SfntTable<'a>], tag: &[u8; 4]) -> Option<&'a [u8]> {
+    tables.iter().find(|(t, _)| t == tag).map(|(_, data)| *data)
+}
+
+/// The offset into `glyf` of each glyph, plus the end of the last one, from a `loca` table
+fn glyph_offsets(loca: &[u8], num_glyphs: usize, long: bool) -> Option<Vec<usize>> {
+    (0..=num_glyphs)
+        .map(|i| {
+            if long {
+                u32_at(loca, i * 4).map(|offset| offset as usize)
+            } else {
+                // Short offsets are stored halved
+                u16_at(loca, i * 2).map(|offset| offset as usize * 2)
+            }
+        })
+        .collect()
+}
+
+/// The glyphs that a composite glyph is built from. Empty for simple glyphs
+fn components(glyph: &[u8]) -> Vec<u16> {
+    let mut found = Vec::new();
+    // Only composite glyphs have a negative number of contours. Their components start after the 10 byte header, each
+    // being flags, a glyph index, then arguments and a transform whose sizes depend on the flags
+    match u16_at(glyph, 0) {
+        Some(contours) if (contours as i16) < 0 => {}
+        _ => return found,
+    }
+    let mut offset = 10;
+    while let (Some(flags), Some(index)) = (u16_at(glyph, offset), u16_at(glyph, offset + 2)) {
+        found.push(index);
+        offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            8
+        } else {
+            6
+        };
+        offset += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    found
+}
+
+/// Group `(codepoint, glyph)` pairs, sorted by codepoint, into `(first, last, first_glyph)` runs where both the
+/// codepoints and the glyphs are consecutive
+fn mapping_runs(mapping: &[(u32, u16)]) -> Vec<(u32, u32, u16)> {
+    let mut runs: Vec<(u32, u32, u16)> = Vec::new();
+    for &(codepoint, glyph) in mapping {
+        match runs.last_mut() {
+            Some((first, last, first_glyph))
+                if codepoint == *last + 1
+                    && glyph as u32 == *first_glyph as u32 + (codepoint - *first) =>
+            {
+                *last = codepoint
+            }
+            _ => runs.push((codepoint, codepoint, glyph)),
+        }
+    }
+    runs
+}
+
+/// A format 4 `cmap` subtable for the runs in the Basic Multilingual Plane, or `None` if there are too many to fit
+fn cmap_format_4(runs: &[(u32, u32, u16)]) -> Option<Vec<u8>> {
+    // Segments can't include U+FFFF, as there must be a final segment for it alone
+    let mut segments: Vec<(u16, u16, u16)> = runs
+        .iter()
+        .filter(|&&(first, _, _)| first < 0xffff)
+        .map(|&(first, last, first_glyph)| {
+            let last = last.min(0xfffe) as u16;
+            let delta = first_glyph.wrapping_sub(first as u16);
+            (first as u16, last, delta)
+        })
+        .collect();
+    segments.push((0xffff, 0xffff, 1));
+    let length = 16 + segments.len() * 8;
+    if length > MAX_FORMAT_4_LENGTH {
+        return None;
+    }
+
+    let count = segments.len() as u16;
+    let selector = 15 - count.leading_zeros() as u16;
+    let search_range = 2 << selector;
+    let mut out = Vec::with_capacity(length);
+    for value in [
+        4,
+        length as u16,
+        0,
+        count * 2,
+        search_range,
+        selector,
+        count * 2 - search_range,
+    ]
+    .iter()
+    {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    out.extend(segments.iter().flat_map(|&(_, last, _)| last.to_be_bytes()));
+    out.extend_from_slice(&[0, 0]);
+    out.extend(
+        segments
+            .iter()
+            .flat_map(|&(first, _, _)| first.to_be_bytes()),
+    );
+    out.extend(
+        segments
+            .iter()
+            .flat_map(|&(_, _, delta)| delta.to_be_bytes()),
+    );
+    // Every glyph comes from the delta, so none of the segments use the glyph ID array
+    out.resize(length, 0);
+    Some(out)
+}
+
+/// A format 12 `cmap` subtable for all of the runs
+fn cmap_format_12(runs: &[(u32, u32, u16)]) -> Vec<u8> {
+    let length = 16 + runs.len() * 12;
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(&[0, 12, 0, 0]);
+    out.extend_from_slice(&(length as u32).to_be_bytes());
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&(runs.len() as u32).to_be_bytes());
+    for &(first, last, first_glyph) in runs {
+        out.extend_from_slice(&first.to_be_bytes());
+        out.extend_from_slice(&last.to_be_bytes());
+        out.extend_from_slice(&(first_glyph as u32).to_be_bytes());
+    }
+    out
+}
+
+/// A `cmap` table with Windows Unicode subtables for `(codepoint, glyph)` pairs sorted by codepoint
+///
+/// There is a format 4 subtable for the BMP, and a format 12 one as well if any characters are outside the BMP or
+/// there are too many to fit in format 4.
+fn build_cmap(mapping: &[(u32, u16)]) -> Vec<u8> {
+    let runs = mapping_runs(mapping);
+    let mut subtables = Vec::new();
+    let format_4 = cmap_format_4(&runs);
+    let complete = format_4.is_some() && mapping.iter().all(|&(codepoint, _)| codepoint < 0xffff);
+    if let Some(format_4) = format_4 {
+        subtables.push((1u16, format_4));
+    }
+    if !complete {
+        subtables.push((10, cmap_format_12(&runs)));
+    }
+
+    // The header is version and the number of encoding records, each platform, encoding and the subtable offset
+    let mut out = Vec::new();
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(&(subtables.len() as u16).to_be_bytes());
+    let mut offset = 4 + subtables.len() * 8;
+    for (encoding, subtable) in &subtables {
+        out.extend_from_slice(&[0, 3]);
+        out.extend_from_slice(&encoding.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        offset += subtable.len();
+    }
+    for (_, subtable) in &subtables {
+        out.extend_from_slice(subtable);
+    }
+    out
+}
+
+/// The sum of the big-endian 32-bit words of `data`, zero padded to a multiple of 4 bytes
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// An OpenType file holding `tables`, with the whole file checksum filled in to the `head` table
+fn write_sfnt(version: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+    tables.sort_by_key(|(tag, _)| *tag);
+    let count = tables.len() as u16;
+    let selector = 15 - count.leading_zeros() as u16;
+    let search_range = 16 << selector;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&version.to_be_bytes());
+    for value in [count, search_range, selector, count * 16 - search_range].iter() {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + tables.len() * 16;
+    let mut head = None;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head = Some(offset);
+        }
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += round4(data.len());
+    }
+    for (_, data) in &tables {
+        out.extend_from_slice(data);
+        out.resize(round4(out.len()), 0);
+    }
+
+    if let Some(head) = head {
+        let adjustment = 0xb1b0afbau32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+/// A font holding only the glyphs for `mapping`, `(codepoint, glyph)` pairs as given by the font's own `cmap`
+///
+/// `.notdef` and the components of any composite glyphs are always kept. `tables` are those of a font with TrueType
+/// outlines, as returned by `tables::sfnt_tables`. Returns `None` if there is no `glyf` table, or any of the tables
+/// that need rewriting are missing or truncated.
+pub fn subset(tables: &[SfntTable], mapping: &[(u32, u16)]) -> Option<Vec<u8>> {
+    let head = find_table(tables, b"head")?;
+    let hhea = find_table(tables, b"hhea")?;
+    let maxp = find_table(tables, b"maxp")?;
+    let hmtx = find_table(tables, b"hmtx")?;
+    let loca = find_table(tables, b"loca")?;
+    let glyf = find_table(tables, b"glyf")?;
+    if head.len() < 54 || hhea.len() < 36 {
+        return None;
+    }
+
+    let num_glyphs = u16_at(maxp, 4)? as usize;
+    let offsets = glyph_offsets(loca, num_glyphs, u16_at(head, 50)? != 0)?;
+    let glyph = |id: u16| glyf.get(offsets[id as usize]..offsets[id as usize + 1]);
+
+    let mut kept = BTreeSet::new();
+    let mut pending: Vec<u16> = Some(0)
+        .into_iter()
+        .chain(mapping.iter().map(|&(_, id)| id))
+        .collect();
+    while let Some(id) = pending.pop() {
+        if (id as usize) < num_glyphs && kept.insert(id) {
+            pending.extend(components(glyph(id)?));
+        }
+    }
+    let count = *kept.iter().next_back()? as usize + 1;
+
+    // Always write long offsets, so the glyphs can be padded to 4 bytes
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((count + 1) * 4);
+    for id in 0..count as u16 {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if kept.contains(&id) {
+            new_glyf.extend_from_slice(glyph(id)?);
+            new_glyf.resize(round4(new_glyf.len()), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    // hmtx is the full metrics of the first numberOfHMetrics glyphs, then just the left side bearings of the rest, so
+    // cutting it off after the last glyph keeps it valid
+    let h_metrics = (u16_at(hhea, 34)? as usize).min(count);
+    let new_hmtx = hmtx
+        .get(..h_metrics * 4 + (count - h_metrics) * 2)?
+        .to_vec();
+
+    let mut new_head = head.to_vec();
+    new_head[8..12].copy_from_slice(&[0; 4]);
+    new_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+    let mut new_hhea = hhea.to_vec();
+    new_hhea[34..36].copy_from_slice(&(h_metrics as u16).to_be_bytes());
+    let mut new_maxp = maxp.to_vec();
+    new_maxp[4..6].copy_from_slice(&(count as u16).to_be_bytes());
+
+    let mut mapping: Vec<(u32, u16)> = mapping.iter().copied().filter(|&(_, id)| id != 0).collect();
+    mapping.sort_unstable();
+    mapping.dedup_by_key(|&mut (codepoint, _)| codepoint);
+
+    let mut out = vec![
+        (*b"head", new_head),
+        (*b"hhea", new_hhea),
+        (*b"maxp", new_maxp),
+        (*b"hmtx", new_hmtx),
+        (*b"loca", new_loca),
+        (*b"glyf", new_glyf),
+        (*b"cmap", build_cmap(&mapping)),
+    ];
+    // Version 3 of post has no glyph names, so only the 32 byte header is needed
+    if let Some(post) = find_table(tables, b"post").and_then(|post| post.get(..32)) {
+        let mut new_post = post.to_vec();
+        new_post[..4].copy_from_slice(&0x00030000u32.to_be_bytes());
+        out.push((*b"post", new_post));
+    }
+    for tag in COPIED_TABLES.iter() {
+        if let Some(data) = find_table(tables, tag) {
+            out.push((**tag, data.to_vec()));
+        }
+    }
+    Some(write_sfnt(TRUETYPE_VERSION, out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::sfnt_tables;
+
+    #[test]
+    fn test_components() {
+        // A simple glyph with one contour
+        assert_eq!(
+            components(&[0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Vec::<u16>::new()
+        );
+        let mut composite = vec![0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0];
+        // Word arguments and a scale, then byte arguments and no transform
+        composite.extend_from_slice(&[0, 0x29, 0, 5, 0, 0, 0, 0, 0x40, 0]);
+        composite.extend_from_slice(&[0, 0, 0, 7, 0, 0]);
+        assert_eq!(components(&composite), vec![5, 7]);
+    }
+
+    #[test]
+    fn test_mapping_runs() {
+        let mapping = [(0x41, 3), (0x42, 4), (0x43, 9), (0x45, 10), (0x1f600, 11)];
+        assert_eq!(
+            mapping_runs(&mapping),
+            vec![
+                (0x41, 0x42, 3),
+                (0x43, 0x43, 9),
+                (0x45, 0x45, 10),
+                (0x1f600, 0x1f600, 11)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_cmap() {
+        let cmap = build_cmap(&[(0x41, 3), (0x42, 4)]);
+        // One subtable, for Windows Unicode BMP, straight after the header
+        assert_eq!(&cmap[..12], &[0, 0, 0, 1, 0, 3, 0, 1, 0, 0, 0, 12]);
+        let format_4 = &cmap[12..];
+        assert_eq!(&format_4[..4], &[0, 4, 0, 32]);
+        // Two segments: end codes, padding, start codes, deltas and range offsets
+        assert_eq!(&format_4[6..14], &[0, 4, 0, 4, 0, 1, 0, 0]);
+        assert_eq!(
+            &format_4[14..32],
+            &[0, 0x42, 0xff, 0xff, 0, 0, 0, 0x41, 0xff, 0xff, 0xff, 0xc2, 0, 1, 0, 0, 0, 0]
+        );
+
+        let cmap = build_cmap(&[(0x41, 3), (0x1f600, 4)]);
+        assert_eq!(&cmap[..4], &[0, 0, 0, 2]);
+        assert_eq!(&cmap[12..20], &[0, 3, 0, 10, 0, 0, 0, 20 + 32]);
+        assert_eq!(&cmap[52..56], &[0, 12, 0, 0]);
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(checksum(&[0, 0, 0, 1, 0, 0, 0, 2]), 3);
+        assert_eq!(checksum(&[1]), 0x01000000);
+        assert_eq!(checksum(&[0xff; 8]), 0xfffffffe);
+    }
+
+    #[test]
+    fn test_subset() {
+        let mut head = vec![0u8; 54];
+        head[12..16].copy_from_slice(&0x5f0f3cf5u32.to_be_bytes());
+        let mut hhea = vec![0u8; 36];
+        hhea[34..36].copy_from_slice(&[0, 2]);
+        let maxp = [0, 0, 0x50, 0, 0, 4];
+        // Two full metrics then two left side bearings
+        let hmtx = [0, 10, 0, 1, 0, 20, 0, 2, 0, 3, 0, 4];
+        // Glyph 0 and 1 are simple, 2 is empty, and 3 is a composite of glyph 1
+        let mut glyf = vec![0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        glyf.extend_from_slice(&[0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]);
+        glyf.extend_from_slice(&[0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0]);
+        let loca = [0, 0, 0, 6, 0, 12, 0, 12, 0, 20];
+        let tables: Vec<SfntTable> = vec![
+            (*b"GSUB", &[1, 2, 3]),
+            (*b"glyf", &glyf),
+            (*b"head", &head),
+            (*b"hhea", &hhea),
+            (*b"hmtx", &hmtx),
+            (*b"loca", &loca),
+            (*b"maxp", &maxp),
+        ];
+
+        assert!(subset(&tables[..1], &[]).is_none());
+        // Only .notdef
+        let font = subset(&tables, &[(0x20, 0)]).unwrap();
+        assert_eq!(checksum(&font), 0xb1b0afba);
+        let (version, parsed) = sfnt_tables(&font, 0).unwrap();
+        assert_eq!(version, TRUETYPE_VERSION);
+        let tags: Vec<_> = parsed.iter().map(|(tag, _)| tag).collect();
+        assert_eq!(
+            tags,
+            vec![b"cmap", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp"]
+        );
+        assert_eq!(
+            find_table(&parsed, b"loca"),
+            Some(&[0, 0, 0, 0, 0, 0, 0, 12][..])
+        );
+        assert_eq!(find_table(&parsed, b"hmtx"), Some(&[0, 10, 0, 1][..]));
+
+        let font = subset(&tables, &[(0x41, 3)]).unwrap();
+        let (_, parsed) = sfnt_tables(&font, 0).unwrap();
+        assert_eq!(find_table(&parsed, b"maxp").unwrap()[4..6], [0, 4]);
+        assert_eq!(find_table(&parsed, b"hhea").unwrap()[34..36], [0, 2]);
+        assert_eq!(find_table(&parsed, b"head").unwrap()[50..52], [0, 1]);
+        assert_eq!(find_table(&parsed, b"hmtx"), Some(&hmtx[..]));
+        let loca: Vec<u32> = find_table(&parsed, b"loca")
+            .unwrap()
+            .chunks(4)
+            .map(|word| u32_at(word, 0).unwrap())
+            .collect();
+        assert_eq!(loca, vec![0, 12, 24, 24, 40]);
+        assert_eq!(&find_table(&parsed, b"glyf").unwrap()[24..], &glyf[24..]);
+    }
+}
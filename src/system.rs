@@ -0,0 +1,81 @@
+use std::rc::Rc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use windows::Win32::Graphics::DirectWrite::*;
+use windows::Win32::Graphics::Gdi::LOGFONTW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    SystemParametersInfoW, NONCLIENTMETRICSW, SPI_GETNONCLIENTMETRICS,
+    SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS,
+};
+
+use crate::errors::WindowsFontError;
+use crate::{FontFamily, FontVariant};
+
+/// Get the fonts the current theme uses for the non-client areas of windows (captions, menus, message boxes etc.)
+pub fn nonclient_metrics() -> windows::core::Result<NONCLIENTMETRICSW> {
+    let mut metrics = NONCLIENTMETRICSW {
+        cbSize: std::mem::size_of::<NONCLIENTMETRICSW>() as u32,
+        ..Default::default()
+    };
+    unsafe {
+        SystemParametersInfoW(
+            SPI_GETNONCLIENTMETRICS,
+            metrics.cbSize,
+            Some(&mut metrics as *mut _ as _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+        .ok()?;
+    }
+    Ok(metrics)
+}
+
+/// Resolve a GDI LOGFONT to the matching variant in the system font collection
+pub fn variant_from_logfont(logfont: &LOGFONTW, py: Python<'_>) -> PyResult<FontVariant> {
+    let font = unsafe {
+        DWriteCreateFactory::<IDWriteFactory>(DWRITE_FACTORY_TYPE_SHARED)
+            .and_then(|factory| factory.GetGdiInterop())
+            .and_then(|interop| interop.CreateFontFromLOGFONT(logfont))
+            .map_err(WindowsFontError::from)?
+    };
+    let ifamily = unsafe { font.GetFontFamily() }.map_err(WindowsFontError::from)?;
+
+    Ok(FontVariant::new(
+        Rc::new(font),
+        Py::new(py, FontFamily::new(ifamily))?,
+    ))
+}
+
+/// The font Windows uses for UI text (the message box font), such as "Segoe UI" on modern versions of Windows
+#[pyfunction]
+#[pyo3(text_signature = "()")]
+pub fn system_ui_font(py: Python<'_>) -> PyResult<FontVariant> {
+    let metrics = nonclient_metrics().map_err(WindowsFontError::from)?;
+    variant_from_logfont(&metrics.lfMessageFont, py)
+}
+
+/// The font the current Windows theme uses for `role`, which is one of:
+///
+/// - ``"caption"``: window title bars
+/// - ``"smcaption"``: the small title bars of tool windows
+/// - ``"menu"``: menu bars and menus
+/// - ``"message"``: message boxes (the same as :func:`system_ui_font`)
+/// - ``"status"``: status bars and tooltips
+#[pyfunction]
+#[pyo3(text_signature = "(role)")]
+pub fn theme_font(role: &str, py: Python<'_>) -> PyResult<FontVariant> {
+    let metrics = nonclient_metrics().map_err(WindowsFontError::from)?;
+    let logfont = match role {
+        "caption" => &metrics.lfCaptionFont,
+        "smcaption" => &metrics.lfSmCaptionFont,
+        "menu" => &metrics.lfMenuFont,
+        "message" => &metrics.lfMessageFont,
+        "status" => &metrics.lfStatusFont,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "role must be one of \"caption\", \"smcaption\", \"menu\", \"message\" or \"status\", not {role:?}"
+            )))
+        }
+    };
+    variant_from_logfont(logfont, py)
+}
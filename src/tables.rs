@@ -0,0 +1,519 @@
+use std::convert::TryInto;
+use std::ffi::c_void;
+
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::{IDWriteFontFace, IDWriteFontFile};
+
+/// The raw data of an OpenType table, borrowed from a font face.
+///
+/// The data is released back to the face when this is dropped.
+pub struct FontTable<'a> {
+    face: &'a IDWriteFontFace,
+    data: &'a [u8],
+    context: *mut c_void,
+}
+
+impl<'a> FontTable<'a> {
+    /// Get the table with the given tag from the face, or `None` if the font doesn't have it
+    pub unsafe fn get(face: &'a IDWriteFontFace, tag: u32) -> windows::core::Result<Option<Self>> {
+        let mut data: *mut c_void = std::ptr::null_mut();
+        let mut size = 0u32;
+        let mut context: *mut c_void = std::ptr::null_mut();
+        let mut exists = BOOL(0);
+
+        face.TryGetFontTable(tag, &mut data, &mut size, &mut context, &mut exists)?;
+        if !exists.as_bool() {
+            return Ok(None);
+        }
+
+        Ok(Some(FontTable {
+            face,
+            data: std::slice::from_raw_parts(data as *const u8, size as usize),
+            context,
+        }))
+    }
+
+    pub fn data(&self) -> &[u8] {
+        self.data
+    }
+
+    pub fn i64_at(&self, offset: usize) -> Option<i64> {
+        let bytes = self.data.get(offset..offset + 8)?;
+        Some(i64::from_be_bytes(bytes.try_into().unwrap()))
+    }
+}
+
+impl Drop for FontTable<'_> {
+    fn drop(&mut self) {
+        unsafe { self.face.ReleaseFontTable(self.context) }
+    }
+}
+
+pub fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+pub fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().unwrap()))
+}
+
+/// The vertical pixels-per-em of each strike in an `EBLC` or `CBLC` table
+pub fn bitmap_location_ppems(data: &[u8]) -> Vec<u16> {
+    // The header is a 4 byte version then the number of BitmapSize records, each of which is 48 bytes with ppemY at
+    // offset 45
+    let count = u32_at(data, 4).unwrap_or(0) as usize;
+    (0..count)
+        .map_while(|i| data.get(8 + i * 48 + 45).map(|&ppem| ppem as u16))
+        .collect()
+}
+
+/// The pixels-per-em of each strike in an `sbix` table
+pub fn sbix_ppems(data: &[u8]) -> Vec<u16> {
+    // The header is version, flags, number of strikes, then a 32-bit offset to each strike. Each strike starts with its
+    // ppem
+    let count = u32_at(data, 4).unwrap_or(0) as usize;
+    (0..count)
+        .map_while(|i| u32_at(data, 8 + i * 4))
+        .filter_map(|offset| u16_at(data, offset as usize))
+        .collect()
+}
+
+/// The `(platform_id, encoding_id, format)` of each subtable in a `cmap` table
+pub fn cmap_subtables(data: &[u8]) -> Vec<(u16, u16, u16)> {
+    // The header is version and the number of encoding records, each of which is 8 bytes ending with the offset to the
+    // subtable. Every subtable format starts with its format number
+    let count = u16_at(data, 2).unwrap_or(0) as usize;
+    (0..count)
+        .map_while(|i| {
+            let record = 4 + i * 8;
+            Some((
+                u16_at(data, record)?,
+                u16_at(data, record + 2)?,
+                u32_at(data, record + 4)?,
+            ))
+        })
+        .filter_map(|(platform_id, encoding_id, offset)| {
+            Some((platform_id, encoding_id, u16_at(data, offset as usize)?))
+        })
+        .collect()
+}
+
+/// The four character foundry code (`achVendID`) of an `OS/2` table, without padding, or `None` if it is blank
+pub fn os2_vendor_id(data: &[u8]) -> Option<String> {
+    let bytes = data.get(58..62)?;
+    let vendor: String = bytes.iter().map(|&b| b as char).collect();
+    let vendor = vendor.trim_matches(|c: char| c == ' ' || c == '\0');
+    if vendor.is_empty() {
+        None
+    } else {
+        Some(vendor.to_owned())
+    }
+}
+
+/// The embedding permissions (`fsType`) of an `OS/2` table
+pub fn os2_fs_type(data: &[u8]) -> Option<u16> {
+    u16_at(data, 8)
+}
+
+/// The tag and data of a table in an OpenType file
+pub type SfntTable<'a> = ([u8; 4], &'a [u8]);
+
+/// The `sfntVersion` and the `(tag, data)` of each table of one face of an OpenType file or collection, in table
+/// directory order, or `None` if the file is truncated or `face_index` is out of range
+pub fn sfnt_tables(file: &[u8], face_index: u32) -> Option<(u32, Vec<SfntTable<'_>>)> {
+    // A collection header is its tag, version and number of fonts, then the offset to each font's table directory
+    let offset = if file.get(0..4)? == b"ttcf" {
+        if face_index >= u32_at(file, 8)? {
+            return None;
+        }
+        u32_at(file, 12 + face_index as usize * 4)? as usize
+    } else {
+        0
+    };
+    // The table directory is the sfnt version, the number of tables and 6 bytes of search hints, then 16 byte records
+    // of tag, checksum, offset (from the start of the file) and length
+    let version = u32_at(file, offset)?;
+    let count = u16_at(file, offset + 4)? as usize;
+    let tables = (0..count)
+        .map(|i| {
+            let record = offset + 12 + i * 16;
+            let tag = file.get(record..record + 4)?.try_into().unwrap();
+            let start = u32_at(file, record + 8)? as usize;
+            let length = u32_at(file, record + 12)? as usize;
+            Some((tag, file.get(start..start.checked_add(length)?)?))
+        })
+        .collect::<Option<_>>()?;
+    Some((version, tables))
+}
+
+/// The script tags (such as `latn` or `arab`) in the script list of a `GSUB` or `GPOS` table
+pub fn layout_script_tags(data: &[u8]) -> Vec<String> {
+    // The header is major and minor version then the offset to the script list. The script list is a count followed by
+    // 6 byte records, each a tag and the offset to that script's table
+    let list = u16_at(data, 4).unwrap_or(0) as usize;
+    let count = u16_at(data, list).unwrap_or(0) as usize;
+    (0..count)
+        .map_while(|i| {
+            let record = list + 2 + i * 6;
+            data.get(record..record + 4)
+        })
+        .map(|tag| tag.iter().map(|&b| b as char).collect())
+        .collect()
+}
+
+/// The names of the 258 standard Macintosh glyphs, which `post` tables refer to by index
+const MAC_GLYPH_NAMES: &str = ".notdef .null nonmarkingreturn space exclam quotedbl numbersign dollar percent ampersand quotesingle parenleft \
+    parenright asterisk plus comma hyphen period slash zero one two three four five six seven eight nine colon \
+    semicolon less equal greater question at A B C D E F G H I J K L M N O P Q R S T U V W X Y Z bracketleft \
+    backslash bracketright asciicircum underscore grave a b c d e f g h i j k l m n o p q r s t u v w x y z \
+    braceleft bar braceright asciitilde Adieresis Aring Ccedilla Eacute Ntilde Odieresis Udieresis aacute agrave \
+    acircumflex adieresis atilde aring ccedilla eacute egrave ecircumflex edieresis iacute igrave icircumflex \
+    idieresis ntilde oacute ograve ocircumflex odieresis otilde uacute ugrave ucircumflex udieresis dagger degree \
+    cent sterling section bullet paragraph germandbls registered copyright trademark acute dieresis notequal AE \
+    Oslash infinity plusminus lessequal greaterequal yen mu partialdiff summation product pi integral ordfeminine \
+    ordmasculine Omega ae oslash questiondown exclamdown logicalnot radical florin approxequal Delta guillemotleft \
+    guillemotright ellipsis nonbreakingspace Agrave Atilde Otilde OE oe endash emdash quotedblleft quotedblright \
+    quoteleft quoteright divide lozenge ydieresis Ydieresis fraction currency guilsinglleft guilsinglright fi fl \
+    daggerdbl periodcentered quotesinglbase quotedblbase perthousand Acircumflex Ecircumflex Aacute Edieresis \
+    Egrave Iacute Icircumflex Idieresis Igrave Oacute Ocircumflex apple Ograve Uacute Ucircumflex Ugrave dotlessi \
+    circumflex tilde macron breve dotaccent ring cedilla hungarumlaut ogonek caron Lslash lslash Scaron scaron \
+    Zcaron zcaron brokenbar Eth eth Yacute yacute Thorn thorn minus multiply onesuperior twosuperior threesuperior \
+    onehalf onequarter threequarters franc Gbreve gbreve Idotaccent Scedilla scedilla Cacute cacute Ccaron ccaron \
+    dcroat";
+
+/// The name of a glyph from a format 1, 2 or 2.5 `post` table, or `None` if the table doesn't name it (as in format
+/// 3, which has no names)
+pub fn post_glyph_name(data: &[u8], glyph: u16) -> Option<String> {
+    let standard = |index: usize| {
+        MAC_GLYPH_NAMES
+            .split_ascii_whitespace()
+            .nth(index)
+            .map(str::to_owned)
+    };
+    // All formats start with a 32 byte header. Formats 2 and 2.5 follow it with the number of glyphs and a per-glyph
+    // array
+    let glyph = glyph as usize;
+    match u32_at(data, 0)? {
+        0x0001_0000 => standard(glyph),
+        0x0002_0000 => {
+            let count = u16_at(data, 32)? as usize;
+            if glyph >= count {
+                return None;
+            }
+            // Indexes past the standard names count into the Pascal strings that follow the array
+            let index = u16_at(data, 34 + glyph * 2)? as usize;
+            if index < 258 {
+                return standard(index);
+            }
+            let mut offset = 34 + count * 2;
+            for _ in 258..index {
+                offset += 1 + *data.get(offset)? as usize;
+            }
+            let length = *data.get(offset)? as usize;
+            let name = data.get(offset + 1..offset + 1 + length)?;
+            Some(name.iter().map(|&b| b as char).collect())
+        }
+        0x0002_5000 => {
+            // Each glyph's offset from its index to the standard name it uses
+            if glyph >= u16_at(data, 32)? as usize {
+                return None;
+            }
+            let offset = *data.get(34 + glyph)? as i8;
+            standard(glyph.checked_add_signed(offset as isize)?)
+        }
+        _ => None,
+    }
+}
+
+/// A single record from the `name` table
+pub struct NameRecord<'a> {
+    pub platform_id: u16,
+    pub encoding_id: u16,
+    pub language_id: u16,
+    pub name_id: u16,
+    pub data: &'a [u8],
+}
+
+/// All the records of a `name` table, skipping any whose string lies outside the table
+pub fn name_records(data: &[u8]) -> Vec<NameRecord<'_>> {
+    // The header is format, count and the offset to the string storage, followed by 12 byte name records
+    let count = u16_at(data, 2).unwrap_or(0) as usize;
+    let storage = u16_at(data, 4).unwrap_or(0) as usize;
+    (0..count)
+        .map_while(|i| {
+            let record = data.get(6 + i * 12..6 + (i + 1) * 12)?;
+            Some((
+                u16_at(record, 0)?,
+                u16_at(record, 2)?,
+                u16_at(record, 4)?,
+                u16_at(record, 6)?,
+                u16_at(record, 8)? as usize,
+                u16_at(record, 10)? as usize,
+            ))
+        })
+        .filter_map(
+            |(platform_id, encoding_id, language_id, name_id, length, offset)| {
+                let start = storage + offset;
+                Some(NameRecord {
+                    platform_id,
+                    encoding_id,
+                    language_id,
+                    name_id,
+                    data: data.get(start..start + length)?,
+                })
+            },
+        )
+        .collect()
+}
+
+// The characters 0x80 to 0xFF of the Mac Roman encoding
+const MAC_ROMAN_HIGH: &str = "ÄÅÇÉÑÖÜáàâäãåçéèêëíìîïñóòôöõúùûü†°¢£§•¶ß®©™´¨≠ÆØ∞±≤≥¥µ∂∑∏π∫ªºΩæø¿¡¬√ƒ≈∆«»…\u{a0}ÀÃÕŒœ–—“”‘’÷◊ÿŸ⁄€‹›ﬁﬂ‡·‚„‰ÂÊÁËÈÍÎÏÌÓÔ\u{f8ff}ÒÚÛÙıˆ˜¯˘˙˚¸˝˛ˇ";
+
+impl NameRecord<'_> {
+    /// Decode the string of this record, or `None` if it is in an encoding we don't support
+    ///
+    /// Unicode and Windows platform strings are UTF-16, and Macintosh platform strings are only supported in the Roman
+    /// encoding.
+    pub fn decode(&self) -> Option<String> {
+        match (self.platform_id, self.encoding_id) {
+            (0, _) | (3, _) => {
+                let units: Vec<u16> = self
+                    .data
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                    .collect();
+                Some(String::from_utf16_lossy(&units))
+            }
+            (1, 0) => Some(
+                self.data
+                    .iter()
+                    .map(|&b| match b {
+                        0..=0x7F => b as char,
+                        _ => MAC_ROMAN_HIGH.chars().nth((b - 0x80) as usize).unwrap(),
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Read the complete contents of a font file through its loader, so this works for in-memory fonts as well as ones
+/// on disk
+pub unsafe fn read_font_file(file: &IDWriteFontFile) -> windows::core::Result<Vec<u8>> {
+    let mut key: *mut c_void = std::ptr::null_mut();
+    let mut key_size = 0u32;
+    file.GetReferenceKey(&mut key, &mut key_size)?;
+    let stream = file.GetLoader()?.CreateStreamFromKey(key, key_size)?;
+
+    let size = stream.GetFileSize()?;
+    let mut fragment: *mut c_void = std::ptr::null_mut();
+    let mut context: *mut c_void = std::ptr::null_mut();
+    stream.ReadFileFragment(&mut fragment, 0, size, &mut context)?;
+    let data = std::slice::from_raw_parts(fragment as *const u8, size as usize).to_vec();
+    stream.ReleaseFileFragment(context);
+    Ok(data)
+}
+
+/// Convert a four character OpenType tag (such as `"head"` or `"wght"`) to its numeric form, or `None` if it isn't
+/// exactly four ASCII characters
+pub fn tag_from_str(tag: &str) -> Option<u32> {
+    let bytes: [u8; 4] = tag.as_bytes().try_into().ok()?;
+    if !tag.is_ascii() {
+        return None;
+    }
+    // DWRITE_MAKE_OPENTYPE_TAG puts the first character in the lowest byte
+    Some(u32::from_le_bytes(bytes))
+}
+
+/// Convert a numeric OpenType tag back to its four character string form
+pub fn tag_to_string(tag: u32) -> String {
+    tag.to_le_bytes().iter().map(|&b| b as char).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitmap_location_ppems() {
+        let mut data = vec![0, 2, 0, 0, 0, 0, 0, 2];
+        for ppem in [12u8, 16] {
+            let mut record = [0u8; 48];
+            record[44] = ppem;
+            record[45] = ppem;
+            data.extend_from_slice(&record);
+        }
+        assert_eq!(bitmap_location_ppems(&data), vec![12, 16]);
+
+        // A truncated table only reports the complete records
+        assert_eq!(bitmap_location_ppems(&data[..60]), vec![12]);
+        assert_eq!(bitmap_location_ppems(&[]), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn test_os2_vendor_id() {
+        let mut data = vec![0u8; 78];
+        data[58..62].copy_from_slice(b"MS  ");
+        assert_eq!(os2_vendor_id(&data), Some("MS".to_owned()));
+        data[58..62].copy_from_slice(b"\0\0\0\0");
+        assert_eq!(os2_vendor_id(&data), None);
+        assert_eq!(os2_vendor_id(&data[..60]), None);
+    }
+
+    #[test]
+    fn test_sfnt_tables() {
+        // A table directory at `start` for two tables, followed by their data
+        let directory = |start: u8| {
+            let mut data = vec![0, 1, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0];
+            data.extend_from_slice(&[
+                b'O',
+                b'S',
+                b'/',
+                b'2',
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                start + 44,
+                0,
+                0,
+                0,
+                2,
+            ]);
+            data.extend_from_slice(&[
+                b'n',
+                b'a',
+                b'm',
+                b'e',
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+                start + 46,
+                0,
+                0,
+                0,
+                1,
+            ]);
+            data.extend_from_slice(&[7, 8, 9]);
+            data
+        };
+        let expected: Vec<SfntTable> = vec![(*b"OS/2", &[7, 8]), (*b"name", &[9])];
+
+        let font = directory(0);
+        assert_eq!(sfnt_tables(&font, 0), Some((0x00010000, expected.clone())));
+        // A table running past the end of the file
+        assert_eq!(sfnt_tables(&font[..46], 0), None);
+
+        // Table offsets in a collection are from the start of the collection
+        let mut collection = vec![
+            b't', b't', b'c', b'f', 0, 1, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 20,
+        ];
+        collection.extend_from_slice(&directory(20));
+        assert_eq!(sfnt_tables(&collection, 1), Some((0x00010000, expected)));
+        assert_eq!(sfnt_tables(&collection, 2), None);
+    }
+
+    #[test]
+    fn test_post_glyph_name() {
+        let mut format1 = vec![0u8; 32];
+        format1[1] = 1;
+        assert_eq!(post_glyph_name(&format1, 0), Some(".notdef".to_owned()));
+        assert_eq!(post_glyph_name(&format1, 36), Some("A".to_owned()));
+        assert_eq!(post_glyph_name(&format1, 257), Some("dcroat".to_owned()));
+        assert_eq!(post_glyph_name(&format1, 258), None);
+
+        let mut format2 = vec![0u8; 32];
+        format2[1] = 2;
+        format2.extend_from_slice(&[0, 3, 0, 0, 1, 3, 1, 2]);
+        format2.extend_from_slice(b"\x03one\x03two");
+        assert_eq!(post_glyph_name(&format2, 0), Some(".notdef".to_owned()));
+        assert_eq!(post_glyph_name(&format2, 1), Some("two".to_owned()));
+        assert_eq!(post_glyph_name(&format2, 2), Some("one".to_owned()));
+        assert_eq!(post_glyph_name(&format2, 3), None);
+        assert_eq!(post_glyph_name(&format2[..45], 1), None);
+
+        let mut format3 = vec![0u8; 32];
+        format3[1] = 3;
+        assert_eq!(post_glyph_name(&format3, 0), None);
+    }
+
+    #[test]
+    fn test_layout_script_tags() {
+        let data = [
+            0, 1, 0, 0, // version
+            0, 10, // script list offset
+            0, 0, 0, 0, // feature and lookup list offsets
+            0, 2, // script count
+            b'D', b'F', b'L', b'T', 0, 14, // first record
+            b'l', b'a', b't', b'n', 0, 20, // second record
+        ];
+        assert_eq!(layout_script_tags(&data), vec!["DFLT", "latn"]);
+
+        // A truncated table only reports the complete records
+        assert_eq!(layout_script_tags(&data[..20]), vec!["DFLT"]);
+        assert_eq!(layout_script_tags(&[]), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_cmap_subtables() {
+        let data = [
+            0, 0, 0, 2, // header
+            0, 3, 0, 1, 0, 0, 0, 20, // Windows BMP record
+            0, 3, 0, 10, 0, 0, 0,
+            99, // Windows full repertoire record, pointing outside the table
+            0, 4, // the format 4 subtable
+        ];
+        assert_eq!(cmap_subtables(&data), vec![(3, 1, 4)]);
+    }
+
+    #[test]
+    fn test_name_records() {
+        let data = [
+            0, 0, 0, 2, 0, 30, // header
+            0, 3, 0, 1, 0x04, 0x09, 0, 1, 0, 4, 0, 0, // Windows record
+            0, 1, 0, 0, 0, 0, 0, 1, 0, 2, 0, 4, // Mac record
+            0, b'H', 0, b'i', b'H', 0x8E, // storage
+        ];
+        let records = name_records(&data);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (
+                records[0].platform_id,
+                records[0].language_id,
+                records[0].name_id
+            ),
+            (3, 0x409, 1)
+        );
+        assert_eq!(records[0].decode().as_deref(), Some("Hi"));
+        assert_eq!(records[1].decode().as_deref(), Some("Hé"));
+
+        // A record pointing past the end of the table is skipped
+        assert_eq!(name_records(&data[..34]).len(), 1);
+    }
+
+    #[test]
+    fn test_mac_roman_table() {
+        assert_eq!(MAC_ROMAN_HIGH.chars().count(), 128);
+    }
+
+    #[test]
+    fn test_sbix_ppems() {
+        let data = [
+            0, 1, 0, 1, 0, 0, 0, 2, // header
+            0, 0, 0, 16, 0, 0, 0, 20, // strike offsets
+            0, 20, 0, 72, // first strike
+            0, 40, 0, 72, // second strike
+        ];
+        assert_eq!(sbix_ppems(&data), vec![20, 40]);
+    }
+}
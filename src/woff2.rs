@@ -0,0 +1,119 @@
+//! Packing of a single font into a WOFF2 file, for serving on the web.
+//!
+//! Tables are stored as they are (using the "null transform" for `glyf` and `loca`), so all the saving comes from the
+//! Brotli compression of the table data.
+
+use crate::brotli;
+use crate::tables::SfntTable;
+
+/// Tags with a one byte code in the table directory, by their index
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+    b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+    b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+    b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+    b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+    b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// The flags marking an arbitrary tag, which follows the flags byte
+const ARBITRARY_TAG: u8 = 63;
+/// The transform version of `glyf` and `loca` that leaves them untouched
+const NULL_TRANSFORM: u8 = 3 << 6;
+
+const HEADER_SIZE: usize = 48;
+
+fn round4(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+/// Append `value` as a UIntBase128: big-endian groups of 7 bits, with the top bit set on all but the last byte
+fn push_base128(out: &mut Vec<u8>, value: u32) {
+    let groups = (1..5).take_while(|i| value >> (7 * i) != 0).count() + 1;
+    for i in (0..groups).rev() {
+        let byte = (value >> (7 * i)) as u8 & 0x7f;
+        out.push(if i > 0 { byte | 0x80 } else { byte });
+    }
+}
+
+/// A WOFF2 file holding the given tables, as returned by `tables::sfnt_tables`
+pub fn encode(flavor: u32, tables: &[SfntTable]) -> Vec<u8> {
+    let mut directory = Vec::new();
+    let mut data = Vec::new();
+    for (tag, table) in tables {
+        let mut flags = KNOWN_TAGS
+            .iter()
+            .position(|known| *known == tag)
+            .map_or(ARBITRARY_TAG, |i| i as u8);
+        if tag == b"glyf" || tag == b"loca" {
+            flags |= NULL_TRANSFORM;
+        }
+        directory.push(flags);
+        if flags & ARBITRARY_TAG == ARBITRARY_TAG {
+            directory.extend_from_slice(tag);
+        }
+        push_base128(&mut directory, table.len() as u32);
+        data.extend_from_slice(table);
+    }
+    let compressed = brotli::compress(&data);
+
+    let sfnt_size =
+        12 + 16 * tables.len() + tables.iter().map(|(_, t)| round4(t.len())).sum::<usize>();
+    let length = round4(HEADER_SIZE + directory.len() + compressed.len());
+
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"wOF2");
+    out.extend_from_slice(&flavor.to_be_bytes());
+    out.extend_from_slice(&(length as u32).to_be_bytes());
+    out.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(&(sfnt_size as u32).to_be_bytes());
+    out.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    // Version 1.0, then the offsets and lengths of the (absent) metadata and private data blocks
+    out.extend_from_slice(&[0, 1, 0, 0]);
+    out.extend_from_slice(&[0; 20]);
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&compressed);
+    out.resize(length, 0);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_base128() {
+        let encoded = |value| {
+            let mut out = Vec::new();
+            push_base128(&mut out, value);
+            out
+        };
+        assert_eq!(encoded(0), vec![0]);
+        assert_eq!(encoded(127), vec![127]);
+        assert_eq!(encoded(128), vec![0x81, 0]);
+        assert_eq!(encoded(64512), vec![0x83, 0xf8, 0]);
+        assert_eq!(encoded(u32::MAX), vec![0x8f, 0xff, 0xff, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn test_encode() {
+        let glyf = [1u8; 10];
+        let tables: Vec<SfntTable> = vec![(*b"glyf", &glyf), (*b"abcd", &[2, 3])];
+        let woff = encode(0x00010000, &tables);
+
+        assert_eq!(&woff[0..4], b"wOF2");
+        assert_eq!(&woff[4..8], &[0, 1, 0, 0]);
+        assert_eq!(woff.len() % 4, 0);
+        assert_eq!(&woff[8..12], &(woff.len() as u32).to_be_bytes());
+        assert_eq!(&woff[12..14], &[0, 2]);
+        // The sfnt header, two table records, and the table data padded to 12 and 4 bytes
+        assert_eq!(&woff[16..20], &(12u32 + 32 + 12 + 4).to_be_bytes());
+        assert_eq!(&woff[24..28], &[0, 1, 0, 0]);
+        assert_eq!(
+            &woff[48..56],
+            &[10 | NULL_TRANSFORM, 10, 63, b'a', b'b', b'c', b'd', 2]
+        );
+    }
+}
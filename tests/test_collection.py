@@ -1,6 +1,8 @@
+import concurrent.futures
+
 import pytest
 
-from windows_fonts import FontCollection
+from windows_fonts import FontCollection, FontReadError, FontSetBuilder, get_matching_variants, open_font
 
 
 @pytest.fixture(scope="module")
@@ -25,3 +27,282 @@ def test_get_index_key_same(collection: FontCollection):
 def test_no_such_font(collection: FontCollection):
     with pytest.raises(KeyError, match=r"unknown font family 'foobarbaznotfound'"):
         collection["foobarbaznotfound"]
+
+
+def test_no_such_font_suggestions(collection: FontCollection):
+    with pytest.raises(KeyError, match=r"unknown font family 'Ariel'; did you mean .*'Arial'"):
+        collection["Ariel"]
+
+    # Nothing is suggested when nothing is close
+    with pytest.raises(KeyError) as exc_info:
+        collection["foobarbaznotfound"]
+    assert "did you mean" not in str(exc_info.value)
+
+
+def test_get_exact(collection: FontCollection):
+    assert collection.get_exact("Arial") == collection["Arial"]
+    assert collection["ARIAL"] == collection["Arial"]
+
+    for name in ("ARIAL", "arial", "Arial Narrow", "foobarbaznotfound"):
+        with pytest.raises(KeyError, match="no font family named exactly"):
+            collection.get_exact(name)
+
+
+@pytest.mark.parametrize("name", ["Arial Narrow", "Arial Bold Italic", "Arial-BoldMT"])
+def test_getitem_font_name_fallback(name: str, collection: FontCollection):
+    # Win32 family, full and PostScript names all find the family the font is in
+    assert collection[name] == collection["Arial"]
+
+
+def test_eq_hash(collection: FontCollection):
+    other = FontCollection()
+
+    assert collection == other
+    assert hash(collection) == hash(other)
+    assert len({collection, other}) == 1
+
+
+def test_family_at(collection: FontCollection):
+    assert collection.family_at(0) == collection[0].name
+
+    with pytest.raises(IndexError):
+        collection.family_at(len(collection))
+
+
+def test_names_set(collection: FontCollection):
+    names = collection.names_set()
+    assert isinstance(names, set)
+    assert "Arial" in names
+    assert names == {name for name, _ in collection.iter_with_names()}
+
+
+def test_family_for_file(collection: FontCollection, tmp_path):
+    arial = collection["Arial"]
+    family = collection.family_for_file(arial.regular.file_path)
+    assert family is not None
+    assert family.name == "Arial"
+
+    # A copy isn't the installed font
+    copy = tmp_path / "arial.ttf"
+    copy.write_bytes(arial.regular.read_bytes())
+    assert collection.family_for_file(copy) is None
+
+    not_a_font = tmp_path / "readme.txt"
+    not_a_font.write_text("hello")
+    with pytest.raises(ValueError, match="not a supported font file"):
+        collection.family_for_file(not_a_font)
+
+
+def test_negative_index(collection: FontCollection):
+    assert collection[-1].name == collection[len(collection) - 1].name
+
+    with pytest.raises(IndexError):
+        collection[-len(collection) - 1]
+
+    # Indices that don't fit in 32 bits don't wrap around to valid ones
+    with pytest.raises(IndexError):
+        collection[-(2**32) - 1]
+    with pytest.raises(IndexError):
+        collection[2**32]
+
+
+def test_reversed(collection: FontCollection):
+    names = [family.name for family in reversed(collection)]
+    assert len(names) == len(collection)
+    assert names[0] == collection[-1].name
+    assert names[-1] == collection[0].name
+
+
+def test_iter_with_names(collection: FontCollection):
+    pairs = list(collection.iter_with_names())
+    assert len(pairs) == len(collection)
+    for name, family in pairs[:10]:
+        assert name == family.name
+    assert "Arial" in dict(pairs)
+
+
+def test_font_set_builder(collection: FontCollection):
+    arial = collection["Arial"]
+
+    builder = FontSetBuilder()
+    builder.add_file(arial.regular.file_path)
+    with open(arial.bold.filename, "rb") as fh:
+        builder.add_bytes(fh.read())
+
+    custom = builder.build()
+    assert len(custom) == 1
+    assert custom[0].name == "Arial"
+    assert len(custom["Arial"]) == 2
+    assert custom != collection
+
+    assert len(FontCollection.from_font_set(builder)) == 1
+
+    from_file = custom["Arial"].get_best_variant()
+    assert from_file.is_installed
+    assert from_file.source == "system"
+
+    from_memory = custom["Arial"].get_best_variant(weight=700)
+    assert not from_memory.is_installed
+    assert from_memory.source == "memory"
+    assert from_memory.install_scope is None
+
+    # Only the variant added by path has a file
+    assert from_file.files() == [arial.regular.filename]
+    assert from_memory.files() == []
+    with pytest.raises(ValueError, match="wasn't loaded from a file"):
+        from_memory.filename
+    assert custom["Arial"].files() == [(arial.regular.filename, [0])]
+
+
+def test_font_set_builder_dropped(collection: FontCollection):
+    # Fonts added from memory can still be read once the builder (and its loader) is gone
+    builder = FontSetBuilder()
+    with open(collection["Arial"].bold.filename, "rb") as fh:
+        builder.add_bytes(fh.read())
+    custom = builder.build()
+    del builder
+
+    bold = custom["Arial"].get_best_variant(weight=700)
+    assert bold.source == "memory"
+    assert bold.has_character("A")
+    assert bold.read_bytes()
+    assert bold.files() == []
+    with pytest.raises(ValueError, match="wasn't loaded from a file"):
+        bold.filename
+    with pytest.raises(FontReadError, match="loaded from memory"):
+        bold.files(verify=True)
+
+
+def test_diff(collection: FontCollection):
+    builder = FontSetBuilder()
+    builder.add_file(collection["Arial"].regular.file_path)
+    custom = builder.build()
+
+    only_custom, only_system, both = custom.diff(collection)
+    assert only_custom == []
+    assert both == ["Arial"]
+    assert "Arial" not in only_system
+    assert "Times New Roman" in only_system
+    assert only_system == sorted(only_system)
+    assert len(only_system) + len(both) == len(set(collection.family_at(i) for i in range(len(collection))))
+
+    assert collection.diff(custom) == (only_system, only_custom, both)
+
+
+def test_get_matching_variants_in_collection(collection: FontCollection):
+    arial = collection["Arial"]
+
+    builder = FontSetBuilder()
+    builder.add_file(arial.regular.file_path)
+    builder.add_file(arial.bold.file_path)
+    custom = builder.build()
+
+    variants = get_matching_variants(collection=custom, win32_family_names="Arial")
+    assert sorted(variant.name for variant in variants) == ["Bold", "Regular"]
+
+    assert get_matching_variants(collection=custom, full_name="Arial Bold Italic") == []
+    assert len(get_matching_variants(full_name="Arial Bold Italic")) == 1
+
+
+def test_font_set_builder_bad_file(tmp_path):
+    path = tmp_path / "not-a-font.ttf"
+    path.write_bytes(b"not a font")
+
+    builder = FontSetBuilder()
+    with pytest.raises(OSError):
+        builder.add_file(path)
+
+
+def test_open_font(collection: FontCollection, tmp_path):
+    # Cambria and Cambria Math share a .ttc file
+    math = collection["Cambria Math"][0]
+    assert math.face_index == 1
+
+    opened = open_font(math.file_path, face_index=1)
+    assert opened.family.name == "Cambria Math"
+    assert opened.face_index == 1
+    assert opened.same_face(math)
+
+    assert open_font(math.file_path).family.name == "Cambria"
+
+    with pytest.raises(IndexError):
+        open_font(math.file_path, face_index=2)
+
+    path = tmp_path / "not-a-font.ttf"
+    path.write_bytes(b"not a font")
+    with pytest.raises(ValueError, match="not a supported font file"):
+        open_font(path)
+
+
+def test_find_all(collection: FontCollection):
+    assert collection.find_all("Arial") == [collection["Arial"]]
+
+    # Arial Narrow isn't a family of its own, but is found in the Arial family
+    assert collection["Arial"] in collection.find_all("Arial Narrow")
+
+    assert collection.find_all("foobarbaznotfound") == []
+
+
+def test_filter(collection: FontCollection):
+    families = collection.filter(lambda family: family.name.startswith("Arial"))
+    names = [family.name for family in families]
+    assert "Arial" in names
+    assert all(name.startswith("Arial") for name in names)
+
+    assert collection.filter(lambda family: False) == []
+
+    def boom(family):
+        raise ZeroDivisionError
+
+    with pytest.raises(ZeroDivisionError):
+        collection.filter(boom)
+
+
+def test_installed_for(collection: FontCollection):
+    machine = FontCollection.installed_for("machine")
+    assert machine["Arial"].regular.install_scope == "machine"
+    assert collection["Arial"].regular.install_scope == "machine"
+
+    user = FontCollection.installed_for("user")
+    for i in range(len(user)):
+        assert user[i].regular.install_scope == "user"
+
+    with pytest.raises(ValueError, match="scope must be"):
+        FontCollection.installed_for("everyone")
+
+
+def test_snapshot(collection: FontCollection):
+    snapshots = collection.snapshot()
+    assert len(snapshots) >= len(collection)
+
+    arial = next(snap for snap in snapshots if snap.family == "Arial" and snap.name == "Regular")
+    variant = collection["Arial"].regular
+    assert arial.weight == 400
+    assert arial.style == variant.style
+    assert arial.stretch == 5
+    assert arial.files == variant.files()
+    assert arial.face_index == 0
+    assert arial.information["full_name"] == "Arial"
+    assert "FontSnapshot" in repr(arial)
+
+    # Snapshots can be used from other threads
+    with concurrent.futures.ThreadPoolExecutor() as pool:
+        names = list(pool.map(lambda snap: snap.information.get("full_name"), snapshots[:20]))
+    assert len(names) == 20
+
+
+def test_from_font_set_with_filter(collection: FontCollection):
+    arial = FontCollection.from_font_set_with_filter(win32_family_names="Arial")
+    assert len(arial) == 1
+    assert arial[0].name == "Arial"
+    assert arial != collection
+
+    cyrillic = FontCollection.from_font_set_with_filter(supported_script_language_tag="Cyrl")
+    assert "Arial" in cyrillic.names_set()
+    assert "Wingdings" not in cyrillic.names_set()
+
+    nested = FontCollection.from_font_set_with_filter(collection=arial, full_name="Arial Bold")
+    assert len(nested[0]) == 1
+
+    with pytest.raises(TypeError, match="no filter conditions"):
+        FontCollection.from_font_set_with_filter()
@@ -6,3 +6,5 @@ from windows_fonts import Style, Weight
 def test_style():
     style = Style.NORMAL
     assert repr(style) == "Style.NORMAL"
+    assert repr(Style.ITALIIC) == "Style.ITALIIC"
+    assert repr(Style.OBLIQUE) == "Style.OBLIQUE"
@@ -2,7 +2,7 @@ import os
 
 import pytest
 
-from windows_fonts import FontCollection, FontFamily, Style, Weight
+from windows_fonts import FontCollection, FontFamily, Style, Weight, get_matching_variants
 
 
 @pytest.fixture(scope="module")
@@ -76,12 +76,120 @@ def test_get_best_match_dwrite3(width, weight, italic, expected_props, family: F
         assert val == expected
 
 
+@pytest.mark.parametrize(
+    ["width", "style", "expected_stem"],
+    [
+        pytest.param(100, Style.NORMAL, "ARIAL.TTF", id="100,NORMAL"),
+        pytest.param(75, Style.NORMAL, "ARIALN.TTF", id="75,NORMAL"),
+        pytest.param(75, Style.ITALIIC, "ARIALNI.TTF", id="75,ITALIC"),
+    ],
+)
+def test_get_best_match_dwrite0_width(width, style, expected_stem, family: FontFamily):
+    """Test the `width` parameter on the _get_dwrite0_matching_variants code path"""
+    var = family.get_best_variant(width=width, style=style)
+
+    assert os.path.basename(var.filename).upper() == expected_stem
+
+
+def test_get_matching_variants_style_with_slant(family: FontFamily):
+    with pytest.raises(Exception, match="cannot pass `style`"):
+        family.get_matching_variants(style=Style.NORMAL, slant=10)
+
+
+@pytest.mark.parametrize(
+    ["attr", "expected_props"],
+    [
+        pytest.param("regular", {"weight": Weight.REGULAR, "style": Style.NORMAL}, id="regular"),
+        pytest.param("bold", {"weight": Weight.BOLD, "style": Style.NORMAL}, id="bold"),
+        pytest.param("italic", {"weight": Weight.REGULAR, "style": Style.ITALIIC}, id="italic"),
+        pytest.param("bold_italic", {"weight": Weight.BOLD, "style": Style.ITALIIC}, id="bold_italic"),
+    ],
+)
+def test_convenience_variants(attr, expected_props, family: FontFamily):
+    var = getattr(family, attr)
+
+    for (name, val) in expected_props.items():
+        assert getattr(var, name) == val
+
+
 def test_get_matching_variants(family: FontFamily):
     variants = family.get_matching_variants()
     assert isinstance(variants, list)
     assert len(variants) > 2
 
 
+def test_get_matching_variants_limit(family: FontFamily):
+    variants = family.get_matching_variants(weight=Weight.BOLD)
+    limited = family.get_matching_variants(weight=Weight.BOLD, limit=2)
+    assert [v.name for v in limited] == [v.name for v in variants[:2]]
+    assert family.get_matching_variants(limit=0) == []
+
+
+def test_get_matching_variants_weight_range(family: FontFamily):
+    variants = family.get_matching_variants(weight=(300, 500))
+    assert variants
+    assert all(variant.weight == Weight.REGULAR for variant in variants)
+
+    # The order of the range doesn't matter, and Weight members can be used
+    variants = family.get_matching_variants(weight=(Weight.BLACK, Weight.SEMI_BOLD))
+    assert variants
+    assert all(variant.weight == Weight.BOLD for variant in variants)
+
+
+def test_get_matching_variants_width_range(family: FontFamily):
+    variants = family.get_matching_variants(width=(50, 90), style=Style.NORMAL)
+    assert variants
+    assert all("Narrow" in variant.name for variant in variants)
+
+
+def test_custom_axes(family: FontFamily, collection: FontCollection):
+    # Bahnschrift is a variable font, so the registered axes can also be given by tag
+    bahnschrift = collection["Bahnschrift"]
+    assert bahnschrift.get_best_variant(custom_axes={"wght": 700}).weight == Weight.BOLD
+
+    # Arial has no such axis, but asking for one isn't an error
+    assert family.get_matching_variants(custom_axes={"GRAD": 50})
+
+    with pytest.raises(ValueError, match="four character axis tag"):
+        family.get_best_variant(custom_axes={"toolong": 1})
+
+    with pytest.raises(ValueError, match="cannot pass `style`"):
+        family.get_matching_variants(style=Style.NORMAL, custom_axes={"GRAD": 50})
+
+
+def test_get_matching_variants_exclude_simulated(collection: FontCollection):
+    # Arial Black only has a single (black, upright) face, so an italic has to be simulated
+    family = collection['Arial Black']
+    variants = family.get_matching_variants(style=Style.ITALIIC)
+    assert any(variant.simulations for variant in variants)
+
+    designed = family.get_matching_variants(style=Style.ITALIIC, exclude_simulated=True)
+    assert designed
+    assert not any(variant.simulations for variant in designed)
+    assert len(designed) < len(variants)
+
+
+def test_matching_reports_simulations(collection: FontCollection):
+    # Impact only has a regular face, so a bold one has to be simulated
+    family = collection["Impact"]
+    bold = family.get_best_variant(weight=Weight.BOLD, style=Style.NORMAL)
+    assert bold.simulations == {"bold"}
+    assert family.get_matching_variants(weight=Weight.BOLD, style=Style.NORMAL)[0].simulations == {"bold"}
+
+    assert family.get_best_variant(weight=Weight.REGULAR, style=Style.NORMAL).simulations == frozenset()
+
+
+def test_typographic_family_name(family: FontFamily):
+    # Segoe UI Semibold has a separate Win32 family, but is grouped with Segoe UI typographically
+    semibold = get_matching_variants(full_name="Segoe UI Semibold")[0].family
+    assert semibold.name == "Segoe UI Semibold"
+    assert semibold.typographic_family_name == "Segoe UI"
+    assert semibold.preferred_family_name == semibold.typographic_family_name
+
+    # Arial's fonts only have Win32 names
+    assert family.typographic_family_name in (None, "Arial")
+
+
 def test_repr(family: FontFamily):
     assert repr(family) == '<FontFamily name="Arial">'
 
@@ -89,3 +197,77 @@ def test_repr(family: FontFamily):
 def test_len(family: FontFamily):
     # Lets just check it's an in int in a plausible range
     assert 2 < len(family) < 25
+
+
+def test_contains(family: FontFamily):
+    assert "Bold Italic" in family
+    assert "bold italic" in family
+    assert "Narrow" in family
+    assert "Extra Wide" not in family
+    assert 1 not in family
+
+
+def test_getitem(family: FontFamily):
+    bold_italic = family["bold italic"]
+    assert bold_italic.weight == Weight.BOLD
+    assert bold_italic.style == Style.ITALIIC
+    assert family[-1].name == family[len(family) - 1].name
+
+    with pytest.raises(KeyError):
+        family["Extra Wide"]
+    with pytest.raises(IndexError):
+        family[len(family)]
+    # Indices that don't fit in 32 bits mustn't wrap around to a real variant
+    with pytest.raises(IndexError):
+        family[2**32]
+    with pytest.raises(IndexError):
+        family[-2**32]
+
+
+def test_files(family: FontFamily, collection: FontCollection):
+    files = family.files()
+    paths = [path for path, _ in files]
+    assert len(paths) == len(set(paths))
+    assert family.regular.filename in paths
+
+    # Cambria and Cambria Math share a single .ttc file
+    cambria = collection["Cambria Math"].files()
+    assert len(cambria) == 1
+    path, indices = cambria[0]
+    assert path.lower().endswith(".ttc")
+    assert indices
+
+
+def test_available_weights_and_stretches(family: FontFamily):
+    weights = family.available_weights()
+    assert 400 in weights
+    assert 700 in weights
+    assert weights == sorted(set(weights))
+
+    # Arial includes the Arial Narrow faces
+    stretches = family.available_stretches()
+    assert 3 in stretches
+    assert 5 in stretches
+    assert stretches == sorted(set(stretches))
+
+
+def test_at_size(family: FontFamily, collection: FontCollection):
+    # Arial has no optical size axis, so at_size makes no difference
+    assert family.get_best_variant(at_size=12).name == family.get_best_variant().name
+
+    # Sitka is a variable font with an opsz axis on recent Windows
+    sitka = collection["Sitka Text"]
+    small = sitka.get_best_variant(at_size=10)
+    large = sitka.get_best_variant(at_size=72)
+    small_opsz = small.axis_value("opsz")
+    large_opsz = large.axis_value("opsz")
+    if small_opsz is None:
+        pytest.skip("Sitka has no opsz axis before Windows 11")
+    assert large_opsz is not None
+    assert small_opsz < large_opsz
+
+
+def test_best_variant_for_text(family: FontFamily):
+    best = family.best_variant_for_text("Hello")
+    assert best.name == "Regular"
+    assert best.has_character("H")
@@ -0,0 +1,112 @@
+import threading
+
+import pytest
+
+from windows_fonts import (
+    FontCollection,
+    FontVariant,
+    capabilities,
+    default_fallback_font,
+    families_by_generic,
+    reload_locale,
+    shutdown,
+    system_ui_font,
+    theme_font,
+    warm_up,
+)
+
+
+def test_system_ui_font():
+    variant = system_ui_font()
+
+    assert isinstance(variant, FontVariant)
+    assert variant.family.name == "Segoe UI"
+
+
+def test_theme_font():
+    for role in ("caption", "smcaption", "menu", "message", "status"):
+        assert isinstance(theme_font(role), FontVariant)
+    assert theme_font("message").family.name == system_ui_font().family.name
+
+    with pytest.raises(ValueError, match="role must be one of"):
+        theme_font("icon")
+
+
+def test_families_by_generic():
+    serif = [family.name for family in families_by_generic("serif")]
+    assert serif[0] == "Times New Roman"
+    assert "Arial" not in serif
+
+    monospace = [family.name for family in families_by_generic("monospace")]
+    assert monospace[:2] == ["Consolas", "Courier New"]
+    assert len(monospace) == len(set(monospace))
+
+    assert families_by_generic("sans-serif", collection=FontCollection())[0].name == "Arial"
+
+    with pytest.raises(ValueError, match="generic must be one of"):
+        families_by_generic("system-ui")
+
+
+def test_shutdown():
+    # shutdown only affects the calling thread, so do it on a new one to not break the rest of the tests
+    errors = []
+
+    def run():
+        variant = FontCollection()["Arial"][0]
+        assert variant.files()
+        shutdown()
+        try:
+            variant.files()
+        except RuntimeError as e:
+            errors.append(e)
+
+    thread = threading.Thread(target=run)
+    thread.start()
+    thread.join()
+
+    assert len(errors) == 1
+    assert "shutdown()" in str(errors[0])
+
+
+def test_warm_up():
+    results = []
+
+    def run():
+        results.append(warm_up())
+        results.append(FontCollection()["Arial"][0].files())
+
+    thread = threading.Thread(target=run)
+    thread.start()
+    thread.join()
+
+    assert results[0] is None
+    assert results[1]
+
+
+def test_reload_locale():
+    locale = reload_locale()
+    # A BCP 47 tag such as "en-US"
+    assert "-" in locale
+    assert FontCollection()["Arial"].name == "Arial"
+
+
+def test_default_fallback_font():
+    variant = default_fallback_font()
+    assert isinstance(variant, FontVariant)
+    assert variant.has_character("\ufffd")
+
+    # A Thai character gets a font that can show it
+    thai = default_fallback_font(0x0E01)
+    assert thai.has_character("\u0e01")
+
+    with pytest.raises(ValueError, match="not a valid Unicode code point"):
+        default_fallback_font(0xD800)
+
+
+def test_capabilities():
+    caps = capabilities()
+    assert set(caps) == {"axis_matching", "color_fonts", "variable_fonts", "typographic_model", "font_fallback"}
+    assert all(isinstance(supported, bool) for supported in caps.values())
+    # The tests run on a recent Windows 10 or later, so everything should be there
+    assert all(caps.values())
+    assert capabilities() == caps
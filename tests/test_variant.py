@@ -1,8 +1,29 @@
 import collections.abc
+import copy
+import datetime
+import json
+import os
+import pathlib
+import pickle
+import re
+import shutil
+import winreg
 
 import pytest
 
-from windows_fonts import FontCollection, FontVariant, Style, Weight, get_matching_variants
+from windows_fonts import (
+    FontCollection,
+    FontReadError,
+    FontSetBuilder,
+    FontVariant,
+    Style,
+    Weight,
+    find_by_wss_family,
+    get_matching_variants,
+    iter_matching_variants,
+    open_font,
+    variant_from_face,
+)
 
 
 @pytest.fixture(scope="module")
@@ -32,6 +53,40 @@ def test_get_matching_variants(collection: FontCollection):
     assert var.family == collection["Arial"]
 
 
+def test_get_matching_variants_limit():
+    variants = get_matching_variants(win32_family_names="Arial")
+    assert len(variants) > 2
+    limited = get_matching_variants(win32_family_names="Arial", limit=2)
+    assert [v.name for v in limited] == [v.name for v in variants[:2]]
+
+
+def test_get_matching_variants_multiple_values():
+    vars = get_matching_variants(full_name=["Arial Bold Italic", "Arial Narrow Bold Italic"])
+
+    assert sorted(var.information["full_name"] for var in vars) == ["Arial Bold Italic", "Arial Narrow Bold Italic"]
+
+    single = get_matching_variants(full_name=("Arial Bold Italic",))
+    assert len(single) == 1
+
+
+def test_iter_matching_variants(collection: FontCollection):
+    it = iter_matching_variants(win32_family_names="Arial")
+
+    assert not isinstance(it, list)
+    var = next(it)
+    assert var.family == collection["Arial"]
+
+    assert len([var, *it]) == len(get_matching_variants(win32_family_names="Arial"))
+
+
+def test_find_by_wss_family():
+    vars = find_by_wss_family("Arial Narrow")
+
+    assert len(vars) == 4
+    for var in vars:
+        assert var.weight_stretch_style_family_name == "Arial Narrow"
+
+
 @pytest.mark.parametrize(
     ["kwargs", "match"],
     [
@@ -46,6 +101,11 @@ def test_get_matching_variants_errors(kwargs, match: str, collection: FontCollec
         get_matching_variants(**kwargs)
 
 
+def test_get_matching_variants_empty_list():
+    with pytest.raises(ValueError, match=r"must have at least one value"):
+        get_matching_variants(full_name=[])
+
+
 def test_name(variant):
     assert variant.name == "Regular"
 
@@ -55,10 +115,103 @@ def test_repr(variant):
     assert rep.startswith('<FontVariant name=Regular, family=<FontFamily name="Arial">,')
 
 
+def test_repr_oblique(collection: FontCollection):
+    # Webdings has no italic face, so asking for oblique gets us a face with a simulated oblique style
+    variant = collection["Webdings"].get_best_variant(style=Style.OBLIQUE)
+
+    assert variant.style == Style.OBLIQUE
+    assert "style=Style.OBLIQUE" in repr(variant)
+
+
+def test_format(family):
+    bold = family["Bold"]
+    assert f"{bold}" == "Bold"
+    assert f"{bold:full}" == "Arial Bold"
+    assert f"{bold:ps}" == "Arial-BoldMT"
+
+    with pytest.raises(ValueError, match="unknown format code"):
+        f"{bold:xyz}"
+
+
 def test_filename(variant):
     variant.filename.lower().endswith("ARIAL.TTF")
 
 
+def test_file_path(variant):
+    assert isinstance(variant.file_path, pathlib.Path)
+    assert variant.file_path == pathlib.Path(variant.filename)
+    assert variant.file_paths() == [pathlib.Path(f) for f in variant.files()]
+
+
+def test_files_verify(variant):
+    assert variant.files(verify=True) == variant.files()
+
+
+def test_files_verify_missing(variant, tmp_path):
+    copy = tmp_path / "arial.ttf"
+    shutil.copyfile(variant.filename, copy)
+    opened = open_font(copy)
+    [path] = opened.files()
+
+    try:
+        copy.rename(tmp_path / "moved.ttf")
+    except PermissionError:
+        pytest.skip("font file is locked while in use")
+
+    with pytest.raises(FontReadError, match="was the font uninstalled") as excinfo:
+        opened.files(verify=True)
+    assert excinfo.value.filename == path
+    # Existing handlers for the builtin error still catch it
+    assert isinstance(excinfo.value, FileNotFoundError)
+
+
+def test_face_index(variant):
+    assert variant.face_index == 0
+
+
+def test_pillow_spec(variant):
+    assert variant.pillow_spec(size=24) == {"font": variant.filename, "index": 0, "size": 24}
+
+
+def test_matplotlib_fontproperties(variant):
+    assert variant.matplotlib_fontproperties() == {
+        "fname": variant.filename,
+        "weight": 400,
+        "style": "normal",
+        "stretch": "normal",
+    }
+
+
+def test_to_json(variant: FontVariant):
+    doc = json.loads(variant.to_json())
+
+    assert doc["family"] == "Arial"
+    assert doc["name"] == "Regular"
+    assert doc["weight"] == {"value": 400, "name": "REGULAR"}
+    assert doc["style"] == "normal"
+    assert doc["stretch"] == "normal"
+    assert doc["files"] == variant.files()
+    assert doc["face_index"] == 0
+    assert doc["postscript_name"] == "ArialMT"
+    assert doc["information"]["copyright"] == variant.information["copyright"]
+
+
+def test_stretch_value(variant: FontVariant):
+    assert variant.stretch_value == 5
+
+    narrow = get_matching_variants(full_name="Arial Narrow")[0]
+    assert narrow.stretch_value == 3
+
+
+def test_weight_and_stretch_names(family):
+    assert family.regular.weight_name == "Regular"
+    assert family.regular.stretch_name == "Normal"
+    assert family.bold.weight_name == "Bold"
+
+    narrow = get_matching_variants(full_name="Arial Narrow Bold")[0]
+    assert (narrow.weight_name, narrow.stretch_name) == ("Bold", "Condensed")
+
+
 def test_style(variant):
     assert isinstance(variant.style, Style)
 
@@ -88,3 +241,766 @@ def test_information(variant: FontVariant):
 
     with pytest.raises(KeyError):
         info['madeup']
+
+
+def test_information_cached_keys(variant: FontVariant):
+    info = variant.information
+    keys = info.keys()
+    assert keys
+
+    # Repeated calls answer from the same cached set of keys
+    for _ in range(2):
+        assert info.keys() == keys
+        assert len(info) == len(keys)
+        assert all(key in info for key in keys)
+        assert "not-a-key" not in info
+        assert [key for key, _ in info.items()] == keys
+        assert bool(info)
+
+    # The cache is per instance, and a fresh one finds the same keys
+    assert variant.information.keys() == keys
+
+
+def test_information_bool(variant: FontVariant):
+    # Before anything else has listed the keys, and after
+    info = variant.information
+    assert bool(info) is True
+    assert len(info) > 0
+    assert bool(info) is True
+
+
+def test_information_sort_order(variant: FontVariant):
+    info = variant.information
+
+    values = info.values()
+    assert values == sorted(values, key=lambda v: (v.lower(), v))
+    assert [key for key, _ in info.items()] == info.keys()
+
+
+def test_designed_for(collection: FontCollection):
+    # Leelawadee UI is the Windows UI font for Thai, Lao and Buginese, and says so in its meta table
+    leelawadee = collection["Leelawadee UI"].regular
+    tags = leelawadee.designed_for
+    assert "Thai" in tags
+    assert len(tags) == len(set(tags))
+    assert all(tag in leelawadee.information["design_script_language_tag"] for tag in tags)
+
+    # Arial has no meta table
+    arial = collection["Arial"].regular
+    assert "design_script_language_tag" not in arial.information
+    assert arial.designed_for == []
+
+
+def test_attribution_getters(variant: FontVariant, collection: FontCollection):
+    assert "The Monotype Corporation" in variant.copyright
+    assert variant.copyright == variant.information["copyright"]
+    assert variant.trademark == "Arial is a trademark of The Monotype Corporation."
+    assert variant.manufacturer == "The Monotype Corporation"
+    assert "Monotype" in variant.designer
+
+    # Marlett only has the basic names, so missing strings give None rather than raising
+    marlett = collection["Marlett"][0]
+    assert "designer" not in marlett.information
+    assert marlett.designer is None
+
+
+def test_provenance_getters(variant: FontVariant):
+    assert variant.version_string == variant.information["versions"]
+    assert variant.version_string.startswith("Version ")
+    assert variant.vendor_id == variant.get_table("OS/2")[58:62].decode("latin-1").strip()
+    assert 1 <= len(variant.vendor_id) <= 4
+
+
+def test_metrics(variant: FontVariant):
+    metrics = variant.metrics
+
+    assert metrics.design_units_per_em == 2048
+    assert metrics.ascent > 0
+    assert metrics.descent > 0
+
+
+def test_scaled_metrics(variant: FontVariant):
+    metrics = variant.metrics
+    scaled = variant.scaled_metrics(20)
+
+    assert scaled.size_px == 20
+    assert scaled.ascent == pytest.approx(metrics.ascent * 20 / metrics.design_units_per_em)
+    assert scaled.line_height == pytest.approx(scaled.ascent + scaled.descent + scaled.line_gap)
+
+    with pytest.raises(ValueError):
+        variant.scaled_metrics(0)
+
+
+def test_metrics_for_dpi(variant: FontVariant):
+    # 12pt at 144 DPI is 24px
+    scaled = variant.metrics_for_dpi(12, 144)
+    assert scaled.size_px == 24
+    assert scaled.ascent == pytest.approx(variant.scaled_metrics(24).ascent)
+
+    assert variant.metrics_for_dpi(12).size_px == 16
+
+    with pytest.raises(ValueError, match="dpi"):
+        variant.metrics_for_dpi(12, 0)
+
+
+def test_underline_strikethrough(variant: FontVariant):
+    metrics = variant.metrics
+    scale = 20 / metrics.design_units_per_em
+
+    offset, thickness = variant.underline(20)
+    assert offset == pytest.approx(metrics.underline_position * scale)
+    assert thickness == pytest.approx(metrics.underline_thickness * scale)
+    assert offset < 0 < thickness
+
+    offset, thickness = variant.strikethrough(20)
+    assert offset == pytest.approx(metrics.strikethrough_position * scale)
+    assert offset > 0 < thickness
+
+    with pytest.raises(ValueError):
+        variant.underline(0)
+
+
+def test_metrics_extended(variant: FontVariant, family):
+    metrics = variant.metrics
+    extended = variant.metrics_extended
+
+    assert extended.design_units_per_em == metrics.design_units_per_em
+    assert extended.ascent == metrics.ascent
+    assert extended.glyph_box_left < 0 < extended.glyph_box_right
+    assert extended.glyph_box_bottom < 0 < extended.glyph_box_top
+    assert extended.subscript_position_y == variant.vertical_metrics.subscript_offset_y
+    assert isinstance(extended.has_typographic_metrics, bool)
+
+    # Upright fonts have a vertical caret
+    assert extended.caret_slope_run == 0
+    assert family.italic.metrics_extended.caret_slope_run > 0
+    assert "ExtendedMetrics" in repr(extended)
+
+
+def test_vertical_metrics(variant: FontVariant):
+    metrics = variant.vertical_metrics
+
+    assert 0 < metrics.subscript_size_y < variant.metrics.design_units_per_em
+    assert 0 < metrics.superscript_size_y < variant.metrics.design_units_per_em
+    assert metrics.superscript_offset_y > 0
+    assert "VerticalMetrics" in repr(metrics)
+
+
+def test_gdi_compatible_metrics(variant: FontVariant):
+    metrics = variant.gdi_compatible_metrics(12)
+
+    assert metrics.design_units_per_em == variant.metrics.design_units_per_em
+    assert metrics.ascent > 0
+
+    with pytest.raises(ValueError):
+        variant.gdi_compatible_metrics(0)
+
+
+def test_outline_svg(variant: FontVariant):
+    svg = variant.outline_svg("A", size=100)
+
+    assert svg.startswith('<path d="M ')
+    assert svg.endswith(' Z"/>')
+
+    # Space has no outline
+    assert variant.outline_svg(" ") == '<path d=""/>'
+
+
+def test_flags(variant: FontVariant, collection: FontCollection):
+    assert isinstance(variant.flags, frozenset)
+    assert "monospaced" not in variant.flags
+
+    mono = collection["Consolas"][0]
+    assert "monospaced" in mono.flags
+
+    symbol = collection["Webdings"][0]
+    assert "symbol" in symbol.flags
+
+
+def test_cmap_subtables(variant: FontVariant, collection: FontCollection):
+    subtables = variant.cmap_subtables()
+    # Arial has a format 4 Windows Unicode BMP subtable
+    assert (3, 1, 4) in subtables
+
+    # Symbol fonts use the Windows symbol encoding
+    webdings = collection["Webdings"][0]
+    assert any(platform == 3 and encoding == 0 for platform, encoding, _ in webdings.cmap_subtables())
+
+
+def test_get_table(variant: FontVariant):
+    head = variant.get_table("head")
+    assert isinstance(head, bytes)
+    # The magic number in the head table
+    assert head[12:16] == b"\x5f\x0f\x3c\xf5"
+
+    assert variant.get_table("zzzz") is None
+
+    with pytest.raises(ValueError):
+        variant.get_table("toolong")
+
+
+def test_glyph_name(variant: FontVariant):
+    assert variant.glyph_name(0) == ".notdef"
+    assert variant.glyph_name(variant.open_face().glyph_indices("A")[0]) == "A"
+    assert variant.glyph_name(65535) is None
+
+
+def test_name_record(variant: FontVariant):
+    # Name ID 1 is the family name, and 6 the PostScript name
+    assert variant.name_record(1) == "Arial"
+    assert variant.name_record(6) == "ArialMT"
+    assert variant.name_record(1, platform=3, encoding=1, language=0x409) == "Arial"
+    assert variant.name_record(1, language=0x7FFF) is None
+    assert variant.name_record(1000) is None
+
+
+def test_open_face(variant: FontVariant):
+    face = variant.open_face()
+
+    assert face.metrics == variant.metrics
+    assert face.face_index == variant.face_index
+    assert face.get_table("head") == variant.get_table("head")
+    assert face.outline_svg("A", size=100) == variant.outline_svg("A", size=100)
+    assert face.glyph_advances("Hello", 20) == variant.glyph_advances("Hello", 20)
+
+    indices = face.glyph_indices("AB\U0001F600")
+    assert len(indices) == 3
+    assert indices[0] != indices[1]
+    assert indices[2] == 0
+
+
+def test_timestamps(variant: FontVariant):
+    created = variant.created
+    modified = variant.modified
+
+    assert isinstance(created, datetime.datetime)
+    assert created.tzinfo == datetime.timezone.utc
+    assert datetime.datetime(1990, 1, 1, tzinfo=datetime.timezone.utc) < created <= modified
+
+
+def test_information_eq(variant: FontVariant, family):
+    assert variant.information == family[0].information
+    assert variant.information != family.bold.information
+
+
+def test_clone_with_simulations(variant: FontVariant):
+    assert variant.simulations == frozenset()
+
+    bold = variant.clone_with_simulations(bold=True)
+    assert bold.simulations == {"bold"}
+    assert "simulated_bold" in bold.flags
+
+    both = variant.clone_with_simulations(bold=True, oblique=True)
+    assert both.simulations == {"bold", "oblique"}
+
+    assert variant.clone_with_simulations().simulations == frozenset()
+
+
+def test_clone_with_simulations_not_installed(variant: FontVariant, tmp_path: pathlib.Path):
+    # A font that isn't in the system collection is looked up in its own collection
+    path = tmp_path / variant.file_path.name
+    path.write_bytes(variant.read_bytes())
+    bold = open_font(path).clone_with_simulations(bold=True)
+    assert bold.simulations == {"bold"}
+    assert bold.file_path == path
+
+
+def test_glyph_image_formats(variant: FontVariant, collection: FontCollection):
+    assert variant.glyph_image_formats() == {"truetype"}
+
+    emoji = collection["Segoe UI Emoji"][0]
+    assert "colr" in emoji.glyph_image_formats()
+
+
+def test_embedded_bitmap_sizes(variant: FontVariant, collection: FontCollection):
+    assert variant.embedded_bitmap_sizes() == []
+
+    # MS Gothic carries bitmap strikes for small sizes
+    sizes = collection["MS Gothic"][0].embedded_bitmap_sizes()
+    assert sizes
+    assert sizes == sorted(set(sizes))
+
+
+def test_best_size(variant: FontVariant, collection: FontCollection):
+    assert variant.best_size(12) is None
+
+    gothic = collection["MS Gothic"][0]
+    sizes = gothic.embedded_bitmap_sizes()
+    for size in sizes:
+        assert gothic.best_size(size) == size
+    assert gothic.best_size(1000) == sizes[-1]
+    assert gothic.best_size(0) == sizes[0]
+
+
+def test_design_axis_ranges(variant: FontVariant, collection: FontCollection):
+    assert variant.design_axis_ranges() == []
+
+    bahnschrift = collection["Bahnschrift"][0]
+    ranges = {tag: (min, default, max) for tag, min, default, max in bahnschrift.design_axis_ranges()}
+    assert "wght" in ranges
+    min, default, max = ranges["wght"]
+    assert min <= default <= max
+    assert min < max
+
+
+def test_design_coordinates(variant: FontVariant, collection: FontCollection):
+    assert variant.design_coordinates() == {}
+
+    bahnschrift = collection["Bahnschrift"]
+    coords = bahnschrift.get_best_variant(weight=Weight.BOLD).design_coordinates()
+    assert "wght" in coords
+    assert all(0.0 <= position <= 1.0 for position in coords.values())
+
+    light = bahnschrift.get_best_variant(weight=Weight.LIGHT).design_coordinates()
+    assert light["wght"] < coords["wght"]
+
+
+def test_font_variation_settings(variant: FontVariant, collection: FontCollection):
+    assert variant.font_variation_settings() == ""
+
+    bold = collection["Bahnschrift"].get_best_variant(weight=Weight.BOLD)
+    settings = bold.font_variation_settings()
+    assert '"wght" 700' in settings
+    assert re.fullmatch(r'"\w{4}" [\d.]+(, "\w{4}" [\d.]+)*', settings)
+
+
+def test_axis_value(variant: FontVariant, collection: FontCollection):
+    # Static fonts have no axes, even though they have a weight
+    assert variant.axis_value("wght") is None
+
+    bold = collection["Bahnschrift"].get_best_variant(weight=Weight.BOLD)
+    assert bold.axis_value("wght") == pytest.approx(700)
+    assert bold.axis_value("GRAD") is None
+
+    with pytest.raises(ValueError):
+        variant.axis_value("wg")
+
+
+def _eudc_font_path():
+    """The system default EUDC font named in the registry for any code page, if there is one"""
+    try:
+        key = winreg.OpenKey(winreg.HKEY_CURRENT_USER, "EUDC")
+    except OSError:
+        return None
+    with key:
+        for i in range(winreg.QueryInfoKey(key)[0]):
+            try:
+                with winreg.OpenKey(key, winreg.EnumKey(key, i)) as code_page:
+                    name, _ = winreg.QueryValueEx(code_page, "SystemDefaultEUDCFont")
+            except OSError:
+                continue
+            # Usually just a file name, relative to the fonts folder
+            path = pathlib.Path(os.environ["WINDIR"], "Fonts", os.path.expandvars(name))
+            if path.is_file():
+                return path
+    return None
+
+
+def test_is_eudc(variant: FontVariant):
+    assert variant.is_eudc is False
+
+    path = _eudc_font_path()
+    if path is None:
+        pytest.skip("no EUDC font is set up")
+    builder = FontSetBuilder()
+    builder.add_file(path)
+    eudc = builder.build()[0][0]
+    assert eudc.is_eudc is True
+
+
+def test_is_empty_font(variant: FontVariant, tmp_path: pathlib.Path):
+    assert variant.is_empty_font is False
+    assert variant.unicode_ranges() != []
+
+    # A subset with no characters has only .notdef, and an empty cmap
+    path = tmp_path / "empty.ttf"
+    path.write_bytes(variant.strip_to_subset([]))
+    empty = open_font(path)
+    assert empty.unicode_ranges() == []
+    assert empty.is_empty_font is True
+
+
+def test_is_scalable(variant: FontVariant):
+    assert variant.is_scalable is True
+
+
+def test_has_vertical_metrics(collection: FontCollection, variant: FontVariant):
+    assert variant.has_vertical_metrics is False
+    assert collection["MS Gothic"][0].has_vertical_metrics is True
+
+
+def test_render_to_png(variant: FontVariant):
+    png = variant.render_to_png("Hello", 32)
+    assert png.startswith(b"\x89PNG\r\n\x1a\n")
+
+    # The IHDR chunk holds the width and height
+    width = int.from_bytes(png[16:20], "big")
+    height = int.from_bytes(png[20:24], "big")
+    assert width > height > 8
+
+    padded = variant.render_to_png("Hello", 32, padding=10)
+    assert int.from_bytes(padded[16:20], "big") == width + 12
+
+    with pytest.raises(ValueError, match="size_px must be positive"):
+        variant.render_to_png("Hello", 0)
+
+
+def test_same_face(variant: FontVariant, family):
+    assert variant.same_face(family[0])
+    assert variant.same_face(copy.copy(variant))
+    assert not variant.same_face(family.bold)
+
+    # A simulated bold of the same file isn't the same face
+    assert not variant.same_face(variant.clone_with_simulations(bold=True))
+
+
+def test_render_glyph(variant: FontVariant):
+    width, height, alpha = variant.render_glyph("W", 32)
+    assert width > 10
+    assert height > 10
+    assert len(alpha) == width * height
+    assert max(alpha) > 200
+
+    # "i" is narrower than "W"
+    assert variant.render_glyph("i", 32)[0] < width
+
+    assert variant.render_glyph(" ", 32) == (0, 0, b"")
+
+    with pytest.raises(ValueError, match="size_px must be positive"):
+        variant.render_glyph("W", 0)
+
+
+def test_render_run(variant: FontVariant):
+    glyph = variant.open_face().glyph_indices("W")[0]
+    assert variant.render_run([glyph], None, None, 32) == variant.render_glyph("W", 32)
+
+    # Spacing the glyphs further apart makes the run wider
+    narrow = variant.render_run([glyph, glyph], [10, 10], None, 32)
+    wide = variant.render_run([glyph, glyph], [40, 40], None, 32)
+    assert wide[0] > narrow[0]
+
+    # Raising one glyph makes the run taller
+    raised = variant.render_run([glyph, glyph], [40, 40], [(0, 0), (0, 10)], 32)
+    assert raised[1] > wide[1]
+
+    with pytest.raises(ValueError, match="same length"):
+        variant.render_run([glyph, glyph], [10], None, 32)
+
+
+def test_render_glyph_as_buffer(variant: FontVariant):
+    width, height, alpha = variant.render_glyph("W", 32)
+    buf_width, buf_height, view = variant.render_glyph("W", 32, as_buffer=True)
+
+    assert (buf_width, buf_height) == (width, height)
+    assert isinstance(view, memoryview)
+    assert view.readonly
+    assert view.shape == (height, width)
+    assert view.format == "B"
+    assert view.tobytes() == alpha
+    assert view[0, 0] == alpha[0]
+
+    _, _, empty = variant.render_glyph(" ", 32, as_buffer=True)
+    assert empty.shape == (0, 0)
+
+
+def test_render_run_as_buffer(variant: FontVariant):
+    glyphs = variant.open_face().glyph_indices("WA")
+    width, height, alpha = variant.render_run(glyphs, None, None, 32)
+    buf_width, buf_height, view = variant.render_run(glyphs, None, None, 32, as_buffer=True)
+
+    assert (buf_width, buf_height) == (width, height)
+    assert isinstance(view, memoryview)
+    assert view.readonly
+    assert view.shape == (height, width)
+    assert view.tobytes() == alpha
+
+
+def test_color_glyph_run(variant: FontVariant, collection: FontCollection):
+    layers = variant.color_glyph_run("A", 32)
+    assert layers == [(variant.open_face().glyph_indices("A")[0], None)]
+
+    emoji = collection["Segoe UI Emoji"][0]
+    layers = emoji.color_glyph_run("\U0001F600", 32)
+    assert len(layers) > 1
+    assert any(color is not None for _, color in layers)
+    for glyph, color in layers:
+        assert glyph > 0
+        if color is not None:
+            assert len(color) == 4
+            assert all(0 <= c <= 255 for c in color)
+
+    with pytest.raises(ValueError, match="size_px must be positive"):
+        variant.color_glyph_run("A", 0)
+
+
+@pytest.mark.parametrize("copier", [copy.copy, copy.deepcopy])
+def test_copy(variant: FontVariant, copier):
+    copied = copier(variant)
+
+    assert copied is not variant
+    assert copied.family is not variant.family
+    assert copied.family == variant.family
+    assert (copied.name, copied.weight, copied.style) == (variant.name, variant.weight, variant.style)
+    assert copied.files() == variant.files()
+
+
+def test_pickle(variant: FontVariant):
+    assert variant.__getstate__() == (variant.filename, variant.face_index, [])
+    # Recreated in one call from the state, with nothing left over for __setstate__
+    assert variant.__reduce__()[1] == variant.__getstate__()
+    assert len(variant.__reduce__()) == 2
+
+    restored = pickle.loads(pickle.dumps(variant))
+    assert (restored.name, restored.weight, restored.style) == (variant.name, variant.weight, variant.style)
+    assert restored.files() == variant.files()
+
+    bold = pickle.loads(pickle.dumps(variant.clone_with_simulations(bold=True)))
+    assert bold.simulations == {"bold"}
+
+
+def test_pickle_uninstalled_file(variant: FontVariant, tmp_path: pathlib.Path):
+    path = tmp_path / variant.file_path.name
+    path.write_bytes(variant.read_bytes())
+    data = pickle.dumps(open_font(path))
+
+    restored = pickle.loads(data)
+    assert restored.name == variant.name
+    assert restored.file_path == path
+    # Opened on its own from the file, rather than found in a collection
+    assert restored.source == "file"
+    assert len(restored.family) == 1
+    assert restored.family[0].name == variant.name
+
+
+def test_pickle_from_memory(variant: FontVariant):
+    builder = FontSetBuilder()
+    builder.add_bytes(variant.read_bytes())
+    from_memory = builder.build()[0][0]
+
+    with pytest.raises(TypeError, match="cannot pickle a FontVariant loaded from memory"):
+        pickle.dumps(from_memory)
+    with pytest.raises(TypeError, match="cannot pickle a FontVariant loaded from memory"):
+        from_memory.__getstate__()
+
+
+def test_setstate_unknown_simulation(variant: FontVariant):
+    with pytest.raises(ValueError, match="unknown simulation"):
+        variant.__setstate__((variant.filename, 0, ["wide"]))
+
+
+def test_variant_from_face_null():
+    with pytest.raises(ValueError, match="must not be NULL"):
+        variant_from_face(0)
+
+
+def test_variant_from_face(collection: FontCollection):
+    for variant in (collection["Arial"].bold_italic, collection["Cambria Math"][0]):
+        face = variant.open_face()
+        resolved = variant_from_face(face.raw_face_ptr)
+
+        assert (resolved.name, resolved.weight, resolved.style) == (variant.name, variant.weight, variant.style)
+        assert resolved.family.name == variant.family.name
+        assert resolved.face_index == face.face_index
+
+
+def test_read_bytes(variant: FontVariant):
+    data = variant.read_bytes()
+    assert data == variant.file_path.read_bytes()
+
+
+def test_hb_face(variant: FontVariant):
+    blob, index = variant.hb_face()
+    assert blob == variant.read_bytes()
+    assert index == variant.face_index
+
+
+def test_to_woff2(variant: FontVariant):
+    woff = variant.to_woff2()
+    assert woff[:4] == b"wOF2"
+    # The flavor is the sfnt version of the original, then the total length
+    assert woff[4:8] == variant.read_bytes()[:4]
+    assert int.from_bytes(woff[8:12], "big") == len(woff)
+    assert len(woff) < len(variant.read_bytes())
+
+
+def test_strip_to_subset(variant: FontVariant, tmp_path: pathlib.Path):
+    font = variant.strip_to_subset("Hello")
+    assert font[:4] == b"\x00\x01\x00\x00"
+    assert len(font) < len(variant.read_bytes()) / 4
+
+    path = tmp_path / "subset.ttf"
+    path.write_bytes(font)
+    subset = open_font(path)
+    assert subset.has_character("H")
+    assert not subset.has_character("Z")
+    assert subset.open_face().glyph_indices("Hello") == variant.open_face().glyph_indices("Hello")
+
+    assert variant.strip_to_subset([ord("H"), "e", "l", "o"]) == font
+
+    with pytest.raises(ValueError):
+        variant.strip_to_subset([0x110000])
+
+
+def test_typographic_names(variant: FontVariant):
+    assert variant.typographic_names() == ("Arial", "Regular")
+
+    # Segoe UI Semibold has a separate Win32 family, but is grouped with Segoe UI typographically
+    semibold = get_matching_variants(full_name="Segoe UI Semibold")[0]
+    assert semibold.typographic_names() == ("Segoe UI", "Semibold")
+
+
+def test_source(variant: FontVariant):
+    assert variant.is_installed
+    assert variant.source == "system"
+
+
+def test_glyph_advances(variant: FontVariant):
+    advances = variant.glyph_advances("iiW", 20)
+    assert len(advances) == 3
+    assert advances[0] == advances[1]
+    assert advances[2] > advances[0]
+
+    # A character Arial doesn't have still gets an advance, from .notdef
+    assert variant.glyph_advances("\U0001F600", 20)[0] > 0
+
+    assert variant.glyph_advances("", 20) == []
+
+
+def test_supported_scripts(variant: FontVariant, collection: FontCollection):
+    scripts = variant.supported_scripts()
+    assert isinstance(scripts, frozenset)
+    assert {"latn", "arab", "hebr"} <= scripts
+
+    assert "deva" in collection["Nirmala UI"][0].supported_scripts()
+    assert "arab" not in collection["Consolas"][0].supported_scripts()
+
+
+def test_glyph_run_width(variant: FontVariant):
+    assert variant.glyph_run_width("iiW", 20) == pytest.approx(sum(variant.glyph_advances("iiW", 20)))
+    assert variant.glyph_run_width("", 20, shaped=True) == 0
+
+    # Without any pairs to kern, shaping makes no difference
+    assert variant.glyph_run_width("iiW", 20, shaped=True) == pytest.approx(variant.glyph_run_width("iiW", 20))
+    # But "AV" is kerned closer together
+    assert variant.glyph_run_width("AV", 100, shaped=True) < variant.glyph_run_width("AV", 100)
+
+
+def test_glyph_run_width_features(variant: FontVariant):
+    # Turning off kerning undoes the kerning of "AV", and passing features implies shaping
+    assert variant.glyph_run_width("AV", 100, features={"kern": 0}) == pytest.approx(variant.glyph_run_width("AV", 100))
+    assert variant.glyph_run_width("AV", 100, features={"kern": 1}) == pytest.approx(
+        variant.glyph_run_width("AV", 100, shaped=True)
+    )
+
+    with pytest.raises(ValueError, match="four character feature tag"):
+        variant.glyph_run_width("AV", 100, features={"kerning": 0})
+
+
+def test_raster_preview_ascii(variant: FontVariant):
+    preview = variant.raster_preview_ascii(40, "Hello")
+    lines = preview.split("\n")
+    assert all(len(line) == 40 for line in lines)
+    # "Hello" is much wider than it is tall, even allowing for terminal cells being twice as tall as wide
+    assert 2 <= len(lines) < 20
+    assert set(preview) - {"\n"} <= set(" .:-=+*#%@")
+    assert "@" in preview
+
+    blocks = variant.raster_preview_ascii(40, "Hello", blocks=True)
+    assert set(blocks) - {"\n"} <= set(" ▀▄█")
+    assert len(blocks.split("\n")) == pytest.approx(len(lines), abs=1)
+
+    assert variant.raster_preview_ascii(40, " ") == ""
+    with pytest.raises(ValueError, match="width must be positive"):
+        variant.raster_preview_ascii(0, "Hello")
+
+
+def test_render_to_png_features(variant: FontVariant):
+    png = variant.render_to_png("AV", 32, features={"kern": 0})
+    assert png.startswith(b"\x89PNG\r\n\x1a\n")
+    assert png != variant.render_to_png("AV", 32, features={"kern": 1})
+
+
+def test_render_to_png_right_to_left(variant: FontVariant):
+    # Hebrew is written right to left, so shaped it should look like the letters the other way round drawn left to
+    # right unshaped (Arial has no contextual forms or kerning for these)
+    shaped = variant.render_to_png("\u05d0\u05d1", 32, features={})
+    assert shaped == variant.render_to_png("\u05d1\u05d0", 32)
+
+
+def test_has_character(variant: FontVariant):
+    assert variant.has_character("A")
+    assert not variant.has_character("\U0001F600")
+
+
+def test_classification(family):
+    bold_italic = family.bold_italic
+    weight, style, stretch = bold_italic.classification
+    assert weight == Weight.BOLD
+    assert style == Style.ITALIIC
+    assert stretch == bold_italic.stretch_value == 5
+
+    assert bold_italic.classification.weight == Weight.BOLD
+    assert type(bold_italic.classification) is type(family.regular.classification)
+
+
+def test_weight_booleans(family):
+    regular = family.regular
+    assert regular.is_regular
+    assert not regular.is_bold
+    assert not regular.is_light
+
+    assert family.bold.is_bold
+    assert not family.bold.is_regular
+    # Italic, so not the plain variant even though it is weight 400
+    assert not family.italic.is_regular
+
+
+def test_nearest_named_weight(family):
+    for variant in family:
+        assert variant.is_standard_weight
+        assert variant.nearest_named_weight == variant.weight
+
+
+def test_family_key(collection: FontCollection, family):
+    keys = {variant.family_key for variant in family}
+    assert len(keys) == 1
+
+    other = collection["Times New Roman"]
+    assert other[0].family_key != family[0].family_key
+
+    grouped = collections.defaultdict(list)
+    for variant in [*family, *other]:
+        grouped[variant.family_key].append(variant)
+    assert sorted(len(group) for group in grouped.values()) == sorted([len(family), len(other)])
+
+
+def test_style_name(family):
+    assert family.bold_italic.style_name == "Bold Italic"
+
+    semibold = get_matching_variants(full_name="Segoe UI Semibold")[0]
+    assert semibold.style_name == "Semibold"
+
+
+def test_unicode_ranges(variant: FontVariant):
+    ranges = variant.unicode_ranges()
+    assert ranges == sorted(ranges)
+    assert any(first <= ord("A") <= last for first, last in ranges)
+
+
+def test_has_characters(variant: FontVariant):
+    assert variant.has_characters(ord("A"), ord("Z")) == list(range(ord("A"), ord("Z") + 1))
+    assert variant.has_characters(0x1F600, 0x1F64F) == []
+
+    covered = variant.has_characters(0x20, 0x3FF)
+    assert covered == [cp for cp in range(0x20, 0x400) if variant.has_character(chr(cp))]
+
+    with pytest.raises(ValueError):
+        variant.has_characters(10, 1)
+
+
+def test_coverage_ratio(variant: FontVariant):
+    assert variant.coverage_ratio("Hello") == 1.0
+    assert variant.coverage_ratio("") == 1.0
+    # Repeated characters only count once
+    assert variant.coverage_ratio("AA\U0001F600") == 0.5
+    assert variant.coverage_ratio("\U0001F600") == 0.0